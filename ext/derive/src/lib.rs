@@ -0,0 +1,153 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    Attribute, Data, DeriveInput, Error, Fields, LitStr, Result, parse_macro_input,
+    spanned::Spanned,
+};
+
+/// Derive `ext::prelude::ToJSCValue`.
+///
+/// Structs with named fields become objects and newtype structs their inner value, every
+/// field must implement `ToJSCValue`. Enums without data become the name of the variant.
+///
+/// Fields and variants accept `#[jsc(rename = "name")]` and fields accept `#[jsc(skip)]`.
+/// `#[jsc(flatten)]` copies the properties of the object a field becomes, the fields after it
+/// replace those with the same name.
+#[proc_macro_derive(ToJSCValue, attributes(jsc))]
+pub fn derive_to_jscvalue(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: &DeriveInput) -> Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => {
+                let mut properties = vec![];
+                for field in &fields.named {
+                    let options = Options::parse(&field.attrs)?;
+                    if options.skip {
+                        continue;
+                    }
+                    let ident = field.ident.as_ref().expect("named field");
+                    if options.flatten {
+                        properties.push(quote! {{
+                            let inner = ::ext::prelude::ToJSCValue::to_jscvalue(&self.#ident, context);
+                            for property in inner.object_enumerate_properties() {
+                                if let Some(field) = inner.object_get_property(&property) {
+                                    value.object_set_property(&property, &field);
+                                }
+                            }
+                        }});
+                        continue;
+                    }
+                    let property = options
+                        .rename
+                        .unwrap_or_else(|| ident.to_string().trim_start_matches("r#").to_string());
+                    properties.push(quote! {
+                        value.object_set_property(
+                            #property,
+                            &::ext::prelude::ToJSCValue::to_jscvalue(&self.#ident, context),
+                        );
+                    });
+                }
+                quote! {
+                    let value = <::ext::__private::jsc::Value as ::ext::prelude::JSCValueExtManual>
+                        ::new_object(context, None, None);
+                    #(#properties)*
+                    value
+                }
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => quote! {
+                ::ext::prelude::ToJSCValue::to_jscvalue(&self.0, context)
+            },
+            Fields::Unit => quote! {
+                ::ext::__private::jsc::Value::new_null(context)
+            },
+            Fields::Unnamed(fields) => {
+                return Err(Error::new(
+                    fields.span(),
+                    "ToJSCValue can not be derived for tuple structs",
+                ));
+            }
+        },
+        Data::Enum(data) => {
+            let mut arms = vec![];
+            for variant in &data.variants {
+                if !matches!(variant.fields, Fields::Unit) {
+                    return Err(Error::new(
+                        variant.span(),
+                        "ToJSCValue can only be derived for enums without data",
+                    ));
+                }
+                let options = Options::parse(&variant.attrs)?;
+                let ident = &variant.ident;
+                let string = options.rename.unwrap_or_else(|| ident.to_string());
+                arms.push(quote! { Self::#ident => #string, });
+            }
+            quote! {
+                let name = match self {
+                    #(#arms)*
+                };
+                ::ext::__private::jsc::Value::new_string(context, Some(name))
+            }
+        }
+        Data::Union(data) => {
+            return Err(Error::new(
+                data.union_token.span(),
+                "ToJSCValue can not be derived for unions",
+            ));
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::ext::prelude::ToJSCValue for #name #ty_generics #where_clause {
+            fn to_jscvalue(
+                &self,
+                context: &::ext::__private::jsc::Context,
+            ) -> ::ext::__private::jsc::Value {
+                #body
+            }
+        }
+    })
+}
+
+#[derive(Default)]
+struct Options {
+    rename: Option<String>,
+    skip: bool,
+    flatten: bool,
+}
+
+impl Options {
+    fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut options = Options::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("jsc")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    options.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    options.skip = true;
+                    Ok(())
+                } else if meta.path.is_ident("flatten") {
+                    options.flatten = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `rename`, `skip` or `flatten`"))
+                }
+            })?;
+        }
+        Ok(options)
+    }
+}
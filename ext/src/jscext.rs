@@ -4,6 +4,7 @@
 
 use gtk::glib::{
     ffi::{GPtrArray, gpointer},
+    gobject_ffi,
     object::IsA,
     translate::*,
     types::StaticType,
@@ -26,8 +27,9 @@ pub trait JSCValueExtManual: IsA<Value> + 'static {
             None => null_mut::<JSCClass>(),
         };
 
+        // The wrapper owns the instance, it is released by the destroy notify of the class
         let instance = match instance {
-            Some(ins) => ins.to_glib_none().0,
+            Some(ins) => ins.into_glib_ptr(),
             None => null_mut::<JSCValue>(),
         };
 
@@ -111,6 +113,13 @@ pub trait JSCContextExtManual: IsA<Context> + 'static {
         parent_class: Option<&Class>,
         // vtable: *mut JSCClassVTable,
     ) -> Option<Class> {
+        // Instances of the classes are `Value`s, see `JSCValueExtManual::new_object`
+        unsafe extern "C" fn destroy_instance(instance: gpointer) {
+            unsafe {
+                gobject_ffi::g_object_unref(instance as *mut _);
+            }
+        }
+
         let vtable = null_mut();
         unsafe {
             from_glib_none(jsc_context_register_class(
@@ -118,7 +127,7 @@ pub trait JSCContextExtManual: IsA<Context> + 'static {
                 name.to_glib_none().0,
                 parent_class.to_glib_none().0,
                 vtable,
-                None,
+                Some(destroy_instance),
             ))
         }
     }
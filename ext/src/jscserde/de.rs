@@ -0,0 +1,279 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use serde::de::{
+    self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess,
+    Visitor,
+};
+
+use super::Error;
+
+/// How deep arrays and objects may be nested, which also stops at cyclic values
+const MAX_DEPTH: usize = 128;
+
+/// Deserializer reading from a `jsc::Value`
+pub struct Deserializer {
+    value: jsc::Value,
+    /// Arrays and objects `value` is nested in
+    depth: usize,
+}
+
+impl Deserializer {
+    pub fn new(value: jsc::Value) -> Self {
+        Self { value, depth: 0 }
+    }
+
+    /// Deserializer of a value of the array or object this one reads
+    fn nested(value: jsc::Value, depth: usize) -> Self {
+        Self {
+            value,
+            depth: depth + 1,
+        }
+    }
+
+    /// Fail rather than follow arrays and objects nested too deep, or referencing themselves
+    fn check_depth(&self) -> Result<(), Error> {
+        if self.depth >= MAX_DEPTH {
+            return Err(Error(format!(
+                "value nested deeper than {MAX_DEPTH} levels, or cyclic"
+            )));
+        }
+        Ok(())
+    }
+
+    fn is_nothing(&self) -> bool {
+        self.value.is_null() || self.value.is_undefined()
+    }
+
+    fn unexpected(&self) -> Error {
+        Error(format!("unsupported value `{}`", self.value.to_str()))
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let value = &self.value;
+        if self.is_nothing() {
+            visitor.visit_unit()
+        } else if value.is_boolean() {
+            visitor.visit_bool(value.to_boolean())
+        } else if value.is_number() {
+            // JS has no integer type, integral numbers are handed out as such so that
+            // they can be deserialized into Rust integers
+            let n = value.to_double();
+            if n.fract() == 0.0 && n >= 0.0 && n < u64::MAX as f64 {
+                visitor.visit_u64(n as u64)
+            } else if n.fract() == 0.0 && n >= i64::MIN as f64 && n < 0.0 {
+                visitor.visit_i64(n as i64)
+            } else {
+                visitor.visit_f64(n)
+            }
+        } else if value.is_string() {
+            visitor.visit_string(value.to_str().to_string())
+        } else if value.is_array() {
+            self.check_depth()?;
+            let len = value
+                .object_get_property("length")
+                .map_or(0, |length| length.to_int32().max(0) as u32);
+            visitor.visit_seq(ArrayAccess {
+                array: self.value,
+                depth: self.depth,
+                index: 0,
+                len,
+            })
+        } else if value.is_object() && !value.is_function() {
+            self.check_depth()?;
+            let keys: Vec<String> = value
+                .object_enumerate_properties()
+                .iter()
+                .map(|key| key.to_string())
+                .collect();
+            visitor.visit_map(ObjectAccess {
+                object: self.value,
+                depth: self.depth,
+                keys: keys.into_iter(),
+                value: None,
+            })
+        } else {
+            Err(self.unexpected())
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.is_nothing() {
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        if self.value.is_string() {
+            return visitor.visit_enum(self.value.to_str().to_string().into_deserializer());
+        }
+
+        // `{variant: value}` with a single key
+        let keys = if self.value.is_object() {
+            self.value.object_enumerate_properties()
+        } else {
+            vec![]
+        };
+        match keys.as_slice() {
+            [variant] => {
+                self.check_depth()?;
+                let value = self.value.object_get_property(variant);
+                visitor.visit_enum(Variant {
+                    variant: variant.to_string(),
+                    value,
+                    depth: self.depth,
+                })
+            }
+            _ => Err(Error(
+                "expected a string or an object with a single key".to_string(),
+            )),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+struct ArrayAccess {
+    array: jsc::Value,
+    depth: usize,
+    index: u32,
+    len: u32,
+}
+
+impl<'de> SeqAccess<'de> for ArrayAccess {
+    type Error = Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Error> {
+        if self.index >= self.len {
+            return Ok(None);
+        }
+        let element = self
+            .array
+            .object_get_property_at_index(self.index)
+            .ok_or_else(|| Error(format!("missing array element {}", self.index)))?;
+        self.index += 1;
+        seed.deserialize(Deserializer::nested(element, self.depth))
+            .map(Some)
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some((self.len - self.index) as usize)
+    }
+}
+
+struct ObjectAccess {
+    object: jsc::Value,
+    depth: usize,
+    keys: std::vec::IntoIter<String>,
+    value: Option<jsc::Value>,
+}
+
+impl<'de> MapAccess<'de> for ObjectAccess {
+    type Error = Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Error> {
+        let Some(key) = self.keys.next() else {
+            return Ok(None);
+        };
+        self.value = self.object.object_get_property(&key);
+        seed.deserialize(key.into_deserializer()).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, Error> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| Error("next_value called before next_key".to_string()))?;
+        seed.deserialize(Deserializer::nested(value, self.depth))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.keys.len())
+    }
+}
+
+struct Variant {
+    variant: String,
+    value: Option<jsc::Value>,
+    depth: usize,
+}
+
+impl<'de> EnumAccess<'de> for Variant {
+    type Error = Error;
+    type Variant = VariantValue;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, VariantValue), Error> {
+        let variant = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((variant, VariantValue(self.value, self.depth)))
+    }
+}
+
+struct VariantValue(Option<jsc::Value>, usize);
+
+impl VariantValue {
+    fn deserializer(self) -> Result<Deserializer, Error> {
+        self.0
+            .map(|value| Deserializer::nested(value, self.1))
+            .ok_or_else(|| Error("missing enum variant value".to_string()))
+    }
+}
+
+impl<'de> VariantAccess<'de> for VariantValue {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        match self.0 {
+            Some(value) => de::Deserialize::deserialize(Deserializer::nested(value, self.1)),
+            None => Ok(()),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self.deserializer()?)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_seq(self.deserializer()?, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        de::Deserializer::deserialize_map(self.deserializer()?, visitor)
+    }
+}
@@ -0,0 +1,200 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Conversion between serde data structures and `jsc::Value`.
+//!
+//! Structs and maps become objects, sequences and tuples become arrays, `None` and unit
+//! become `null`. Enum variants follow serde's externally tagged representation: unit
+//! variants are strings, any other variant an object with the variant name as single key.
+
+mod de;
+mod ser;
+
+use serde::{Serialize, de::DeserializeOwned};
+
+use std::fmt;
+
+pub use de::Deserializer;
+pub use ser::Serializer;
+
+#[derive(Debug)]
+pub struct Error(String);
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl serde::ser::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+impl serde::de::Error for Error {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Error(msg.to_string())
+    }
+}
+
+/// Serialize `value` into a `jsc::Value` of `context`
+pub fn to_jscvalue<T: Serialize + ?Sized>(
+    context: &jsc::Context,
+    value: &T,
+) -> Result<jsc::Value, Error> {
+    value.serialize(Serializer::new(context))
+}
+
+/// Deserialize an instance of `T` from `value`
+pub fn from_jscvalue<T: DeserializeOwned>(value: &jsc::Value) -> Result<T, Error> {
+    T::deserialize(Deserializer::new(value.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize, de::IgnoredAny};
+
+    use std::collections::{BTreeMap, HashMap};
+
+    use super::{from_jscvalue, to_jscvalue};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Kind {
+        Unit,
+        Newtype(u32),
+        Tuple(i32, String),
+        Struct { enabled: bool },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Inner {
+        name: String,
+        ratio: f64,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Outer {
+        id: u64,
+        offset: i32,
+        label: Option<String>,
+        missing: Option<Inner>,
+        inner: Inner,
+        kinds: Vec<Kind>,
+        nested: BTreeMap<String, HashMap<String, Vec<u8>>>,
+    }
+
+    /// Convert `value` to a `jsc::Value` and back
+    fn round_trip<T>(value: &T) -> T
+    where
+        T: Serialize + serde::de::DeserializeOwned,
+    {
+        let context = jsc::Context::new();
+        let jscvalue = to_jscvalue(&context, value).expect("value is serializable");
+        from_jscvalue(&jscvalue).expect("value is deserializable")
+    }
+
+    #[test]
+    fn round_trip_struct() {
+        let mut nested = BTreeMap::new();
+        nested.insert(
+            "outer".to_string(),
+            HashMap::from([("a".to_string(), vec![1, 2]), ("b".to_string(), vec![])]),
+        );
+        nested.insert("empty".to_string(), HashMap::new());
+        let value = Outer {
+            id: 42,
+            offset: -7,
+            label: Some("label".to_string()),
+            missing: None,
+            inner: Inner {
+                name: "inner".to_string(),
+                ratio: 0.5,
+            },
+            kinds: vec![
+                Kind::Unit,
+                Kind::Newtype(3),
+                Kind::Tuple(-1, "tuple".to_string()),
+                Kind::Struct { enabled: true },
+            ],
+            nested,
+        };
+        assert_eq!(round_trip(&value), value);
+    }
+
+    #[test]
+    fn round_trip_option() {
+        assert_eq!(round_trip(&Some(1.25)), Some(1.25));
+        assert_eq!(round_trip(&None::<String>), None);
+        assert_eq!(round_trip(&vec![Some(1), None]), vec![Some(1), None]);
+    }
+
+    #[test]
+    fn round_trip_enum() {
+        for kind in [
+            Kind::Unit,
+            Kind::Newtype(0),
+            Kind::Tuple(i32::MIN, String::new()),
+            Kind::Struct { enabled: false },
+        ] {
+            assert_eq!(round_trip(&kind), kind);
+        }
+    }
+
+    #[test]
+    fn enum_representation() {
+        let context = jsc::Context::new();
+        let unit = to_jscvalue(&context, &Kind::Unit).unwrap();
+        assert!(unit.is_string());
+        assert_eq!(unit.to_str().as_str(), "Unit");
+
+        let newtype = to_jscvalue(&context, &Kind::Newtype(3)).unwrap();
+        assert!(newtype.is_object());
+        let tagged = newtype.object_get_property("Newtype").unwrap();
+        assert_eq!(tagged.to_int32(), 3);
+    }
+
+    #[test]
+    fn none_is_null() {
+        let context = jsc::Context::new();
+        assert!(to_jscvalue(&context, &None::<u8>).unwrap().is_null());
+        assert!(to_jscvalue(&context, &()).unwrap().is_null());
+    }
+
+    #[test]
+    fn wrong_type_is_an_error() {
+        let context = jsc::Context::new();
+        let value = to_jscvalue(&context, "not a number").unwrap();
+        assert!(from_jscvalue::<u32>(&value).is_err());
+    }
+
+    #[test]
+    fn cyclic_value_is_an_error() {
+        let context = jsc::Context::new();
+        for code in [
+            "const object = {}; object.self = object; object",
+            "const array = [1]; array.push([array]); array",
+            "globalThis",
+        ] {
+            let value = context.evaluate(code).unwrap();
+            assert!(from_jscvalue::<IgnoredAny>(&value).is_err(), "{code}");
+        }
+    }
+
+    #[test]
+    fn deep_value_is_an_error() {
+        let context = jsc::Context::new();
+        let value = context
+            .evaluate("let value = 0; for (let i = 0; i < 200; i++) value = [value]; value")
+            .unwrap();
+        assert!(from_jscvalue::<IgnoredAny>(&value).is_err());
+
+        let value = context
+            .evaluate("let shallow = 0; for (let i = 0; i < 100; i++) shallow = [shallow]; shallow")
+            .unwrap();
+        assert!(from_jscvalue::<IgnoredAny>(&value).is_ok());
+    }
+}
@@ -0,0 +1,366 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use serde::ser::{self, Serialize};
+
+use super::Error;
+use crate::jscext::JSCValueExtManual;
+
+/// Serializer producing `jsc::Value`s of a context
+#[derive(Clone, Copy)]
+pub struct Serializer<'a> {
+    context: &'a jsc::Context,
+}
+
+impl<'a> Serializer<'a> {
+    pub fn new(context: &'a jsc::Context) -> Self {
+        Self { context }
+    }
+
+    fn number(self, n: f64) -> jsc::Value {
+        jsc::Value::new_number(self.context, n)
+    }
+
+    fn object(self) -> jsc::Value {
+        jsc::Value::new_object(self.context, None, None)
+    }
+
+    /// `{variant: value}`, the externally tagged representation of enum variants
+    fn tagged(self, variant: &str, value: &jsc::Value) -> jsc::Value {
+        let object = self.object();
+        object.object_set_property(variant, value);
+        object
+    }
+}
+
+impl<'a> ser::Serializer for Serializer<'a> {
+    type Ok = jsc::Value;
+    type Error = Error;
+
+    type SerializeSeq = SerializeArray<'a>;
+    type SerializeTuple = SerializeArray<'a>;
+    type SerializeTupleStruct = SerializeArray<'a>;
+    type SerializeTupleVariant = SerializeArray<'a>;
+    type SerializeMap = SerializeObject<'a>;
+    type SerializeStruct = SerializeObject<'a>;
+    type SerializeStructVariant = SerializeObject<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<jsc::Value, Error> {
+        Ok(jsc::Value::new_boolean(self.context, v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<jsc::Value, Error> {
+        Ok(self.number(v.into()))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<jsc::Value, Error> {
+        Ok(self.number(v.into()))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<jsc::Value, Error> {
+        Ok(self.number(v.into()))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<jsc::Value, Error> {
+        Ok(self.number(v as f64))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<jsc::Value, Error> {
+        Ok(self.number(v.into()))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<jsc::Value, Error> {
+        Ok(self.number(v.into()))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<jsc::Value, Error> {
+        Ok(self.number(v.into()))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<jsc::Value, Error> {
+        Ok(self.number(v as f64))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<jsc::Value, Error> {
+        Ok(self.number(v.into()))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<jsc::Value, Error> {
+        Ok(self.number(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<jsc::Value, Error> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<jsc::Value, Error> {
+        Ok(jsc::Value::new_string(self.context, Some(v)))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<jsc::Value, Error> {
+        let values: Vec<jsc::Value> = v.iter().map(|b| self.number((*b).into())).collect();
+        Ok(jsc::Value::new_array_from_garray(self.context, &values))
+    }
+
+    fn serialize_none(self) -> Result<jsc::Value, Error> {
+        Ok(jsc::Value::new_null(self.context))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<jsc::Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<jsc::Value, Error> {
+        Ok(jsc::Value::new_null(self.context))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<jsc::Value, Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<jsc::Value, Error> {
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<jsc::Value, Error> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<jsc::Value, Error> {
+        let value = value.serialize(self)?;
+        Ok(self.tagged(variant, &value))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray<'a>, Error> {
+        Ok(SerializeArray {
+            serializer: self,
+            values: Vec::with_capacity(len.unwrap_or_default()),
+            variant: None,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray<'a>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray<'a>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray<'a>, Error> {
+        let mut array = self.serialize_seq(Some(len))?;
+        array.variant = Some(variant);
+        Ok(array)
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<SerializeObject<'a>, Error> {
+        Ok(SerializeObject {
+            serializer: self,
+            object: self.object(),
+            key: None,
+            variant: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeObject<'a>, Error> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeObject<'a>, Error> {
+        let mut object = self.serialize_map(Some(len))?;
+        object.variant = Some(variant);
+        Ok(object)
+    }
+}
+
+pub struct SerializeArray<'a> {
+    serializer: Serializer<'a>,
+    values: Vec<jsc::Value>,
+    variant: Option<&'static str>,
+}
+
+impl SerializeArray<'_> {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.values.push(value.serialize(self.serializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<jsc::Value, Error> {
+        let array = jsc::Value::new_array_from_garray(self.serializer.context, &self.values);
+        Ok(match self.variant {
+            Some(variant) => self.serializer.tagged(variant, &array),
+            None => array,
+        })
+    }
+}
+
+impl ser::SerializeSeq for SerializeArray<'_> {
+    type Ok = jsc::Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<jsc::Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray<'_> {
+    type Ok = jsc::Value;
+    type Error = Error;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<jsc::Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray<'_> {
+    type Ok = jsc::Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<jsc::Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeArray<'_> {
+    type Ok = jsc::Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<jsc::Value, Error> {
+        self.finish()
+    }
+}
+
+pub struct SerializeObject<'a> {
+    serializer: Serializer<'a>,
+    object: jsc::Value,
+    key: Option<String>,
+    variant: Option<&'static str>,
+}
+
+impl SerializeObject<'_> {
+    fn set<T: Serialize + ?Sized>(&mut self, key: &str, value: &T) -> Result<(), Error> {
+        let value = value.serialize(self.serializer)?;
+        self.object.object_set_property(key, &value);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<jsc::Value, Error> {
+        Ok(match self.variant {
+            Some(variant) => self.serializer.tagged(variant, &self.object),
+            None => self.object,
+        })
+    }
+}
+
+impl ser::SerializeMap for SerializeObject<'_> {
+    type Ok = jsc::Value;
+    type Error = Error;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Error> {
+        // Property names are strings, numbers are accepted the way JS coerces them
+        let key = key.serialize(self.serializer)?;
+        if !key.is_string() && !key.is_number() {
+            return Err(Error("object keys must be strings or numbers".to_string()));
+        }
+        self.key = Some(key.to_str().to_string());
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), Error> {
+        let key = self
+            .key
+            .take()
+            .ok_or_else(|| Error("serialize_value called before serialize_key".to_string()))?;
+        self.set(&key, value)
+    }
+
+    fn end(self) -> Result<jsc::Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for SerializeObject<'_> {
+    type Ok = jsc::Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.set(key, value)
+    }
+
+    fn end(self) -> Result<jsc::Value, Error> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeObject<'_> {
+    type Ok = jsc::Value;
+    type Error = Error;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), Error> {
+        self.set(key, value)
+    }
+
+    fn end(self) -> Result<jsc::Value, Error> {
+        self.finish()
+    }
+}
@@ -2,10 +2,23 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+// Lets `#[derive(ToJSCValue)]` refer to `::ext` from within this crate
+extern crate self as ext;
+
 mod jscext;
+mod jscserde;
 mod lightdmext;
+mod tojscvalue;
+
+pub use jscserde::{Deserializer, Error, Serializer, from_jscvalue, to_jscvalue};
 
 pub mod prelude {
     pub use super::jscext::{JSCClassExtManual, JSCContextExtManual, JSCValueExtManual};
-    pub use super::lightdmext::ToJSCValue;
+    pub use super::tojscvalue::ToJSCValue;
+    pub use ext_derive::ToJSCValue;
+}
+
+#[doc(hidden)]
+pub mod __private {
+    pub use jsc;
 }
@@ -0,0 +1,113 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::jscext::JSCValueExtManual;
+
+pub trait ToJSCValue {
+    fn to_jscvalue(&self, context: &jsc::Context) -> jsc::Value;
+}
+
+impl<T: ToJSCValue + ?Sized> ToJSCValue for &T {
+    fn to_jscvalue(&self, context: &jsc::Context) -> jsc::Value {
+        (**self).to_jscvalue(context)
+    }
+}
+
+impl ToJSCValue for jsc::Value {
+    fn to_jscvalue(&self, _context: &jsc::Context) -> jsc::Value {
+        self.clone()
+    }
+}
+
+impl ToJSCValue for () {
+    fn to_jscvalue(&self, context: &jsc::Context) -> jsc::Value {
+        jsc::Value::new_null(context)
+    }
+}
+
+impl ToJSCValue for bool {
+    fn to_jscvalue(&self, context: &jsc::Context) -> jsc::Value {
+        jsc::Value::new_boolean(context, *self)
+    }
+}
+
+macro_rules! impl_number {
+    ($($ty:ty),*) => {
+        $(
+            impl ToJSCValue for $ty {
+                fn to_jscvalue(&self, context: &jsc::Context) -> jsc::Value {
+                    jsc::Value::new_number(context, *self as f64)
+                }
+            }
+        )*
+    };
+}
+
+impl_number!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64);
+
+impl ToJSCValue for str {
+    fn to_jscvalue(&self, context: &jsc::Context) -> jsc::Value {
+        jsc::Value::new_string(context, Some(self))
+    }
+}
+
+impl ToJSCValue for String {
+    fn to_jscvalue(&self, context: &jsc::Context) -> jsc::Value {
+        self.as_str().to_jscvalue(context)
+    }
+}
+
+impl ToJSCValue for gtk::glib::GString {
+    fn to_jscvalue(&self, context: &jsc::Context) -> jsc::Value {
+        self.as_str().to_jscvalue(context)
+    }
+}
+
+impl<T: ToJSCValue> ToJSCValue for Option<T> {
+    fn to_jscvalue(&self, context: &jsc::Context) -> jsc::Value {
+        match self {
+            Some(value) => value.to_jscvalue(context),
+            None => jsc::Value::new_null(context),
+        }
+    }
+}
+
+impl<T: ToJSCValue> ToJSCValue for [T] {
+    fn to_jscvalue(&self, context: &jsc::Context) -> jsc::Value {
+        let values: Vec<jsc::Value> = self.iter().map(|v| v.to_jscvalue(context)).collect();
+        jsc::Value::new_array_from_garray(context, &values)
+    }
+}
+
+impl<T: ToJSCValue> ToJSCValue for Vec<T> {
+    fn to_jscvalue(&self, context: &jsc::Context) -> jsc::Value {
+        self.as_slice().to_jscvalue(context)
+    }
+}
+
+impl<T: ToJSCValue> ToJSCValue for BTreeMap<String, T> {
+    fn to_jscvalue(&self, context: &jsc::Context) -> jsc::Value {
+        object_from(context, self.iter())
+    }
+}
+
+impl<T: ToJSCValue, S> ToJSCValue for HashMap<String, T, S> {
+    fn to_jscvalue(&self, context: &jsc::Context) -> jsc::Value {
+        object_from(context, self.iter())
+    }
+}
+
+fn object_from<'a, T, I>(context: &jsc::Context, entries: I) -> jsc::Value
+where
+    T: ToJSCValue + 'a,
+    I: Iterator<Item = (&'a String, &'a T)>,
+{
+    let value = jsc::Value::new_object(context, None, None);
+    for (key, entry) in entries {
+        value.object_set_property(key, &entry.to_jscvalue(context));
+    }
+    value
+}
@@ -1,6 +1,12 @@
+use std::path::PathBuf;
+
 fn main() {
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    std::fs::write(out_dir.join("bridge.js"), protocol::javascript())
+        .expect("Failed to write bridge.js");
+
     glib_build_tools::compile_resources(
-        &["src/resources"],
+        &[PathBuf::from("src/resources"), out_dir],
         "src/resources/resources.gresource.xml",
         "greeter.gresource",
     );
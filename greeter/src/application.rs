@@ -13,31 +13,41 @@ use gtk::{
 
 use std::rc::Rc;
 
-use crate::{bridge::Dispatcher, browser::Browser, settings::Settings, webview::webview_new};
+use crate::{
+    bridge::Dispatcher,
+    browser::Browser,
+    keybindings::{Action, Profile},
+    settings::Settings,
+    webview::webview_new,
+};
 
 const PRIMARY_MONITOR: usize = 0;
 const WEB_EXTENSIONS_DIR: &str = "/usr/lib/lightdm-webkit-greeter";
 
-pub fn on_activate(app: &Application, debug: bool, theme: Option<&str>) {
-    let config = Settings::new(debug, theme);
+pub fn on_activate(app: &Application, config: Settings) {
     let debug = config.debug_mode();
+    let keybindings = config.keybindings();
+    let permissions = config.permissions();
 
     let secure_mode = config.secure_mode();
     let detect_theme_error = config.detect_theme_errors();
 
-    let api = if let Ok((content, _)) =
-        File::for_uri("resource:///com/github/zaynchen/lightdm-webkit-greeter/lightdm.js")
-            .load_contents(Cancellable::NONE)
-    {
-        String::from_utf8(content.to_vec()).unwrap()
-    } else {
-        "".to_string()
+    // The theme API objects are native classes of the web process extension, the classes they
+    // depend on are evaluated before they are created and the helpers written in JS after
+    let load_scripts = |scripts: &[&str]| {
+        scripts
+            .iter()
+            .map(|script| load_script(script))
+            .collect::<Vec<_>>()
+            .join("\n")
     };
+    let prelude = load_scripts(&["bridge.js", "signal.js"]);
+    let api = load_scripts(&["lightdm.js", "legacy.js"]);
 
     let webcontext = webkit::WebContext::default().expect("default web context does not exist");
     webcontext.set_cache_model(webkit::CacheModel::DocumentViewer);
     webcontext.connect_initialize_web_process_extensions(move |context: &webkit::WebContext| {
-        let data = (secure_mode, detect_theme_error, &api).to_variant();
+        let data = (secure_mode, detect_theme_error, &prelude, &api, debug).to_variant();
         logger_debug!("Extension initialized");
 
         context.set_web_process_extensions_directory(WEB_EXTENSIONS_DIR);
@@ -72,11 +82,12 @@ pub fn on_activate(app: &Application, debug: bool, theme: Option<&str>) {
             };
             Browser::builder()
                 .debug_mode(debug)
+                .keybindings(keybindings)
                 .id(id)
                 .geometry(geometry)
                 .primary(is_primary)
                 .application(app)
-                .webview(webview_new(debug, theme_file))
+                .webview(webview_new(debug, permissions, theme_file))
                 .build()
         })
         .collect();
@@ -88,40 +99,21 @@ pub fn on_activate(app: &Application, debug: bool, theme: Option<&str>) {
     ));
     browsers.iter().for_each(|browser| {
         browser.connect_user_message_received(dispatcher.clone());
-    })
+    });
+    dispatcher.watch();
 }
 
-pub fn on_startup(app: &Application) {
-    app.set_accels_for_action("app.quit", &["<Ctl>Q"]);
-    app.set_accels_for_action("win.toggle-inspector", &["<Ctl><Shift>I", "F12"]);
-
-    app.set_accels_for_action("win.undo", &["<Ctl>Z"]);
-    app.set_accels_for_action("win.redo", &["<Ctl><Shift>Z"]);
-    app.set_accels_for_action("win.cut", &["<Ctl>X"]);
-    app.set_accels_for_action("win.copy", &["<Ctl>C"]);
-    app.set_accels_for_action("win.paste", &["<Ctl>V"]);
-    app.set_accels_for_action("win.paste-plain", &["<Ctl><Shift>V"]);
-    app.set_accels_for_action("win.select-all", &["<Ctl>A"]);
-
-    app.set_accels_for_action("win.zoom-normal", &["<Ctl>0", "<Ctl>KP_0"]);
-    app.set_accels_for_action(
-        "win.zoom-in",
-        &["<Ctl>plus", "<Ctl>equal", "<Ctl>KP_Add", "ZoomIn"],
-    );
-    app.set_accels_for_action(
-        "win.zoom-out",
-        &["<Ctl>minus", "<Ctl>KP_Subtract", "ZoomOut"],
-    );
-    app.set_accels_for_action("win.fullscreen", &["F11"]);
-    app.set_accels_for_action("win.reload", &["<Ctl>R", "F5", "Refresh", "Reload"]);
-    app.set_accels_for_action("win.force-reload", &["<Ctl><Shift>R", "<Shift>F5"]);
-
-    app.set_accels_for_action("win.close", &["<Ctl>W"]);
-    app.set_accels_for_action("win.minimize", &["<Ctl>M"]);
+pub fn on_startup(app: &Application, keybindings: &Profile) {
+    keybindings.iter().for_each(|(action, accels)| {
+        let accels: Vec<&str> = accels.iter().map(|s| s.as_str()).collect();
+        app.set_accels_for_action(action.detailed_name(), &accels);
+    });
 
-    app.add_action_entries([ActionEntry::builder("quit")
-        .activate(|app: &Application, _, _| app.quit())
-        .build()]);
+    if keybindings.contains_key(&Action::Quit) {
+        app.add_action_entries([ActionEntry::builder(Action::Quit.name())
+            .activate(|app: &Application, _, _| app.quit())
+            .build()]);
+    }
 
     app.set_menubar(
         gtk::Builder::from_resource("/com/github/zaynchen/lightdm-webkit-greeter/menubar.ui")
@@ -130,6 +122,15 @@ pub fn on_startup(app: &Application) {
     );
 }
 
+fn load_script(name: &str) -> String {
+    let uri = format!("resource:///com/github/zaynchen/lightdm-webkit-greeter/{name}");
+    if let Ok((content, _)) = File::for_uri(&uri).load_contents(Cancellable::NONE) {
+        String::from_utf8(content.to_vec()).unwrap()
+    } else {
+        "".to_string()
+    }
+}
+
 fn set_cursor(display: &gtk::gdk::Display) {
     if display.backend().is_x11() {
         logger_debug!("Setup root window cursor: GDK backend is X11");
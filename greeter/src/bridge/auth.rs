@@ -0,0 +1,499 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use serde::Serialize;
+
+use std::fmt;
+
+use super::pam::PromptPurpose;
+
+/// Step of the authentication flow
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum AuthState {
+    #[default]
+    Idle,
+    /// Authentication was started without a username, LightDM is about to prompt for it
+    AwaitingUser,
+    Prompting,
+    /// Waiting for LightDM, after authentication was started or a prompt answered
+    Verifying,
+    Authenticated,
+    Failed,
+    StartingSession,
+}
+
+impl fmt::Display for AuthState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match self {
+            AuthState::Idle => "idle",
+            AuthState::AwaitingUser => "awaiting_user",
+            AuthState::Prompting => "prompting",
+            AuthState::Verifying => "verifying",
+            AuthState::Authenticated => "authenticated",
+            AuthState::Failed => "failed",
+            AuthState::StartingSession => "starting_session",
+        };
+        write!(f, "{state}")
+    }
+}
+
+/// What moves the authentication flow forward, requested by the theme or reported by LightDM
+#[derive(Clone, Debug)]
+pub(super) enum Transition {
+    /// `authenticate` and its variants, `with_user` unless LightDM should prompt for a username,
+    /// with the key of the session of `authenticate_remote`
+    Authenticate {
+        with_user: bool,
+        remote_session: Option<String>,
+    },
+    Prompt {
+        ty: i32,
+        purpose: Option<PromptPurpose>,
+    },
+    /// `password_change` if the message announces that the password must be changed
+    Message {
+        ty: i32,
+        password_change: bool,
+    },
+    Respond,
+    Complete {
+        authenticated: bool,
+    },
+    Cancel,
+    StartSession,
+    SessionFailed,
+    Reset,
+}
+
+impl fmt::Display for Transition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Transition::Authenticate { .. } => write!(f, "authenticate"),
+            Transition::Prompt { .. } => write!(f, "show_prompt"),
+            Transition::Message { .. } => write!(f, "show_message"),
+            Transition::Respond => write!(f, "respond"),
+            Transition::Complete { .. } => write!(f, "authentication_complete"),
+            Transition::Cancel => write!(f, "cancel_authentication"),
+            Transition::StartSession => write!(f, "start_session"),
+            Transition::SessionFailed => write!(f, "session_failed"),
+            Transition::Reset => write!(f, "reset"),
+        }
+    }
+}
+
+/// State of the authentication flow as reported to themes by `auth_state_changed`
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub(super) struct AuthStatus {
+    pub(super) state: AuthState,
+    /// Type of the pending prompt
+    pub(super) prompt_type: Option<i32>,
+    /// What the pending prompt asks for, when changing the password
+    pub(super) prompt_purpose: Option<PromptPurpose>,
+    /// Type of the last message of the current authentication
+    pub(super) message_type: Option<i32>,
+    /// Failed attempts since the last successful authentication
+    pub(super) attempts: u32,
+    /// Key of the remote session the current authentication is for
+    pub(super) remote_session: Option<String>,
+    /// PAM asks for a new password, as the current one expired
+    pub(super) password_change: bool,
+}
+
+impl AuthStatus {
+    /// The status after `transition`, `None` if it changes nothing.
+    ///
+    /// LightDM is trusted to report transitions which fit its own state, the theme can only start
+    /// authenticating when not already doing so, answer a pending prompt and start a session once
+    /// authenticated.
+    pub(super) fn next(&self, transition: Transition) -> Result<Option<AuthStatus>, String> {
+        use AuthState::*;
+
+        let status = |state| AuthStatus {
+            state,
+            prompt_type: None,
+            prompt_purpose: None,
+            ..self.clone()
+        };
+        let next = match (self.state, transition) {
+            (
+                Idle | Authenticated | Failed,
+                Transition::Authenticate {
+                    with_user,
+                    remote_session,
+                },
+            ) => AuthStatus {
+                message_type: None,
+                remote_session,
+                password_change: false,
+                ..status(if with_user { Verifying } else { AwaitingUser })
+            },
+            (_, Transition::Prompt { ty, purpose }) => AuthStatus {
+                prompt_type: Some(ty),
+                prompt_purpose: purpose,
+                password_change: self.password_change || purpose.is_some(),
+                ..status(Prompting)
+            },
+            (
+                _,
+                Transition::Message {
+                    ty,
+                    password_change,
+                },
+            ) => AuthStatus {
+                message_type: Some(ty),
+                password_change: self.password_change || password_change,
+                ..self.clone()
+            },
+            (Prompting, Transition::Respond) => status(Verifying),
+            (AwaitingUser | Prompting | Verifying, Transition::Complete { authenticated }) => {
+                if authenticated {
+                    AuthStatus {
+                        attempts: 0,
+                        password_change: false,
+                        ..status(Authenticated)
+                    }
+                } else {
+                    AuthStatus {
+                        attempts: self.attempts + 1,
+                        password_change: false,
+                        ..status(Failed)
+                    }
+                }
+            }
+            // A late report of an authentication which was cancelled
+            (_, Transition::Complete { .. }) => self.clone(),
+            (Idle, Transition::Cancel) => self.clone(),
+            (AwaitingUser | Prompting | Verifying | Authenticated | Failed, Transition::Cancel) => {
+                AuthStatus {
+                    message_type: None,
+                    remote_session: None,
+                    password_change: false,
+                    ..status(Idle)
+                }
+            }
+            (Authenticated, Transition::StartSession) => status(StartingSession),
+            (StartingSession, Transition::SessionFailed) => status(Authenticated),
+            (_, Transition::Reset) => AuthStatus::default(),
+            (state, transition) => {
+                return Err(format!("lightdm.{transition} is not allowed while {state}"));
+            }
+        };
+        Ok((next != *self).then_some(next))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn authenticate(with_user: bool) -> Transition {
+        Transition::Authenticate {
+            with_user,
+            remote_session: None,
+        }
+    }
+
+    fn prompt() -> Transition {
+        Transition::Prompt {
+            ty: 1,
+            purpose: None,
+        }
+    }
+
+    fn authenticate_remote(session: &str) -> Transition {
+        Transition::Authenticate {
+            with_user: true,
+            remote_session: Some(session.to_string()),
+        }
+    }
+
+    /// Apply `transitions` from the idle state, each of them must change the status
+    fn run(transitions: Vec<Transition>) -> AuthStatus {
+        transitions
+            .into_iter()
+            .fold(AuthStatus::default(), |status, transition| {
+                status
+                    .next(transition)
+                    .expect("transition is allowed")
+                    .expect("transition changes the status")
+            })
+    }
+
+    #[test]
+    fn local_authentication_succeeds() {
+        let status = run(vec![authenticate(false)]);
+        assert_eq!(status.state, AuthState::AwaitingUser);
+
+        let status = run(vec![
+            authenticate(false),
+            prompt(),
+            Transition::Respond,
+            Transition::Complete {
+                authenticated: true,
+            },
+        ]);
+        assert_eq!(status.state, AuthState::Authenticated);
+        assert_eq!(status.remote_session, None);
+
+        let status = status.next(Transition::StartSession).unwrap().unwrap();
+        assert_eq!(status.state, AuthState::StartingSession);
+    }
+
+    #[test]
+    fn failed_attempts_are_counted() {
+        let failed = Transition::Complete {
+            authenticated: false,
+        };
+        let status = run(vec![
+            authenticate(true),
+            prompt(),
+            Transition::Respond,
+            failed.clone(),
+            authenticate(true),
+            prompt(),
+            Transition::Respond,
+            failed,
+        ]);
+        assert_eq!(status.state, AuthState::Failed);
+        assert_eq!(status.attempts, 2);
+
+        let status = run(vec![
+            authenticate(true),
+            Transition::Complete {
+                authenticated: false,
+            },
+            authenticate(true),
+            Transition::Complete {
+                authenticated: true,
+            },
+        ]);
+        assert_eq!(status.attempts, 0);
+    }
+
+    #[test]
+    fn authenticate_while_authenticating_is_rejected() {
+        for status in [
+            run(vec![authenticate(false)]),
+            run(vec![authenticate(true)]),
+            run(vec![authenticate(true), prompt()]),
+            run(vec![authenticate(true), prompt(), Transition::Respond]),
+        ] {
+            assert!(status.next(authenticate(true)).is_err(), "{}", status.state);
+            assert!(
+                status.next(authenticate(false)).is_err(),
+                "{}",
+                status.state
+            );
+        }
+
+        // Once cancelled, authentication can start over
+        let status = run(vec![authenticate(true), prompt(), Transition::Cancel]);
+        assert_eq!(status.state, AuthState::Idle);
+        assert!(status.next(authenticate(true)).is_ok());
+    }
+
+    #[test]
+    fn respond_without_a_prompt_is_rejected() {
+        // Before any prompt
+        assert!(AuthStatus::default().next(Transition::Respond).is_err());
+        assert!(
+            run(vec![authenticate(false)])
+                .next(Transition::Respond)
+                .is_err()
+        );
+        assert!(
+            run(vec![authenticate(true)])
+                .next(Transition::Respond)
+                .is_err()
+        );
+
+        // After cancel
+        let cancelled = run(vec![authenticate(true), prompt(), Transition::Cancel]);
+        assert_eq!(
+            cancelled.next(Transition::Respond),
+            Err("lightdm.respond is not allowed while idle".to_string())
+        );
+
+        // Twice to the same prompt
+        let responded = run(vec![authenticate(true), prompt(), Transition::Respond]);
+        assert!(responded.next(Transition::Respond).is_err());
+    }
+
+    #[test]
+    fn start_session_before_success_is_rejected() {
+        for status in [
+            AuthStatus::default(),
+            run(vec![authenticate(false)]),
+            run(vec![authenticate(true), prompt()]),
+            run(vec![authenticate(true), prompt(), Transition::Respond]),
+            run(vec![
+                authenticate(true),
+                Transition::Complete {
+                    authenticated: false,
+                },
+            ]),
+        ] {
+            assert!(
+                status.next(Transition::StartSession).is_err(),
+                "{}",
+                status.state
+            );
+        }
+
+        // Nor twice
+        let starting = run(vec![
+            authenticate(true),
+            Transition::Complete {
+                authenticated: true,
+            },
+            Transition::StartSession,
+        ]);
+        assert!(starting.next(Transition::StartSession).is_err());
+    }
+
+    #[test]
+    fn remote_authentication_succeeds() {
+        let status = run(vec![authenticate_remote("rdp")]);
+        assert_eq!(status.state, AuthState::Verifying);
+        assert_eq!(status.remote_session.as_deref(), Some("rdp"));
+
+        let status = run(vec![
+            authenticate_remote("rdp"),
+            Transition::Prompt {
+                ty: 1,
+                purpose: None,
+            },
+        ]);
+        assert_eq!(status.state, AuthState::Prompting);
+        assert_eq!(status.prompt_type, Some(1));
+        assert_eq!(status.remote_session.as_deref(), Some("rdp"));
+
+        let status = run(vec![
+            authenticate_remote("rdp"),
+            Transition::Prompt {
+                ty: 1,
+                purpose: None,
+            },
+            Transition::Respond,
+            Transition::Complete {
+                authenticated: true,
+            },
+            Transition::StartSession,
+        ]);
+        assert_eq!(status.state, AuthState::StartingSession);
+        assert_eq!(status.prompt_type, None);
+        assert_eq!(status.remote_session.as_deref(), Some("rdp"));
+    }
+
+    #[test]
+    fn remote_authentication_fails() {
+        let status = run(vec![
+            authenticate_remote("rdp"),
+            Transition::Complete {
+                authenticated: false,
+            },
+        ]);
+        assert_eq!(status.state, AuthState::Failed);
+        assert_eq!(status.attempts, 1);
+
+        // Another remote session can be tried right away
+        let status = status.next(authenticate_remote("vnc")).unwrap().unwrap();
+        assert_eq!(status.state, AuthState::Verifying);
+        assert_eq!(status.remote_session.as_deref(), Some("vnc"));
+        assert_eq!(status.attempts, 1);
+    }
+
+    #[test]
+    fn cancel_forgets_the_remote_session() {
+        let status = run(vec![authenticate_remote("rdp"), Transition::Cancel]);
+        assert_eq!(status, AuthStatus::default());
+    }
+
+    #[test]
+    fn local_authentication_has_no_remote_session() {
+        let status = run(vec![
+            authenticate_remote("rdp"),
+            Transition::Cancel,
+            Transition::Authenticate {
+                with_user: false,
+                remote_session: None,
+            },
+        ]);
+        assert_eq!(status.state, AuthState::AwaitingUser);
+        assert_eq!(status.remote_session, None);
+    }
+
+    #[test]
+    fn theme_requests_out_of_order_are_rejected() {
+        let prompting = run(vec![
+            authenticate_remote("rdp"),
+            Transition::Prompt {
+                ty: 1,
+                purpose: None,
+            },
+        ]);
+        assert!(prompting.next(authenticate_remote("vnc")).is_err());
+        assert!(prompting.next(Transition::StartSession).is_err());
+        assert!(AuthStatus::default().next(Transition::Respond).is_err());
+        assert!(
+            run(vec![authenticate_remote("rdp")])
+                .next(Transition::Respond)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn lightdm_reports_are_accepted() {
+        // A late report of a cancelled authentication changes nothing
+        let complete = Transition::Complete {
+            authenticated: true,
+        };
+        assert_eq!(AuthStatus::default().next(complete), Ok(None));
+        assert_eq!(AuthStatus::default().next(Transition::Cancel), Ok(None));
+
+        let failed = run(vec![
+            authenticate_remote("rdp"),
+            Transition::Complete {
+                authenticated: true,
+            },
+            Transition::StartSession,
+            Transition::SessionFailed,
+        ]);
+        assert_eq!(failed.state, AuthState::Authenticated);
+        assert_eq!(
+            failed.next(Transition::Reset).unwrap(),
+            Some(AuthStatus::default())
+        );
+    }
+
+    #[test]
+    fn password_change_is_remembered_until_completion() {
+        let status = run(vec![
+            authenticate_remote("rdp"),
+            Transition::Message {
+                ty: 0,
+                password_change: true,
+            },
+            Transition::Prompt {
+                ty: 1,
+                purpose: Some(PromptPurpose::Current),
+            },
+        ]);
+        assert!(status.password_change);
+        assert_eq!(status.prompt_purpose, Some(PromptPurpose::Current));
+
+        let status = status
+            .next(Transition::Respond)
+            .unwrap()
+            .unwrap()
+            .next(Transition::Complete {
+                authenticated: true,
+            })
+            .unwrap()
+            .unwrap();
+        assert!(!status.password_change);
+        assert_eq!(status.attempts, 0);
+    }
+}
@@ -0,0 +1,121 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use gtk::glib::{self, ControlFlow};
+
+use std::{
+    cell::Cell,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::Duration,
+};
+
+const BACKLIGHT_DIR: &str = "/sys/class/backlight";
+const STEP_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Display backlight, controlled through sysfs
+pub(super) struct Backlight {
+    device: Option<PathBuf>,
+    steps: u32,
+    target: Rc<Cell<i32>>,
+}
+
+impl Backlight {
+    pub(super) fn new(enabled: bool, steps: u32) -> Self {
+        let device = if enabled { find_device() } else { None };
+        match &device {
+            Some(device) => logger_debug!("Backlight device: {}", device.display()),
+            None if enabled => logger_warn!("No backlight device found in {BACKLIGHT_DIR}"),
+            None => {}
+        }
+
+        let backlight = Self {
+            device,
+            steps,
+            target: Default::default(),
+        };
+        backlight.target.set(backlight.brightness().unwrap_or(-1));
+        backlight
+    }
+
+    pub(super) fn available(&self) -> bool {
+        self.device.is_some()
+    }
+
+    /// Current brightness in percent
+    pub(super) fn brightness(&self) -> Option<i32> {
+        let device = self.device.as_ref()?;
+        let current = read_number(&device.join("brightness"))?;
+        let max = read_number(&device.join("max_brightness"))?;
+        if max <= 0 {
+            return None;
+        }
+        Some((current * 100 / max) as i32)
+    }
+
+    /// Change the brightness to `percent`, in `steps` steps, then call `done`
+    pub(super) fn set_brightness<F>(&self, percent: i32, done: F)
+    where
+        F: Fn() + 'static,
+    {
+        let Some(device) = self.device.clone() else {
+            return;
+        };
+        let Some(current) = self.brightness() else {
+            return;
+        };
+
+        let target = percent.clamp(0, 100);
+        self.target.set(target);
+        if self.steps == 0 || current == target {
+            write_brightness(&device, target);
+            done();
+            return;
+        }
+
+        let steps = self.steps as i32;
+        let step = Cell::new(0);
+        let latest_target = self.target.clone();
+        glib::timeout_add_local(STEP_INTERVAL, move || {
+            // A newer change supersedes this one
+            if latest_target.get() != target {
+                return ControlFlow::Break;
+            }
+            step.set(step.get() + 1);
+            let value = current + (target - current) * step.get() / steps;
+            write_brightness(&device, value);
+            if step.get() < steps {
+                ControlFlow::Continue
+            } else {
+                done();
+                ControlFlow::Break
+            }
+        });
+    }
+}
+
+fn find_device() -> Option<PathBuf> {
+    let mut devices: Vec<PathBuf> = std::fs::read_dir(BACKLIGHT_DIR)
+        .ok()?
+        .filter_map(|ent| ent.ok())
+        .map(|ent| ent.path())
+        .filter(|path| path.join("max_brightness").is_file())
+        .collect();
+    devices.sort();
+    devices.into_iter().next()
+}
+
+fn read_number(path: &Path) -> Option<i64> {
+    std::fs::read_to_string(path).ok()?.trim().parse().ok()
+}
+
+fn write_brightness(device: &Path, percent: i32) {
+    let Some(max) = read_number(&device.join("max_brightness")) else {
+        return;
+    };
+    let value = max * percent.clamp(0, 100) as i64 / 100;
+    if let Err(e) = std::fs::write(device.join("brightness"), value.to_string()) {
+        logger_error!("Can not set brightness of {}: {e}", device.display());
+    }
+}
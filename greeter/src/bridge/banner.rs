@@ -0,0 +1,49 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::cell::Cell;
+
+/// Acknowledgement of the login banner, reported by `greeter_config` and required by `lightdm`
+/// before authenticating or starting a session
+#[derive(Default)]
+pub(super) struct BannerState {
+    required: Cell<bool>,
+    acknowledged: Cell<bool>,
+}
+
+impl BannerState {
+    pub(super) fn new(required: bool) -> Self {
+        Self {
+            required: Cell::new(required),
+            ..Default::default()
+        }
+    }
+
+    /// Follow a reloaded configuration
+    pub(super) fn set_required(&self, required: bool) {
+        self.required.set(required);
+    }
+
+    pub(super) fn required(&self) -> bool {
+        self.required.get()
+    }
+
+    pub(super) fn acknowledged(&self) -> bool {
+        self.acknowledged.get()
+    }
+
+    pub(super) fn acknowledge(&self) {
+        self.acknowledged.set(true);
+    }
+
+    /// Whether authentication waits for the banner to be acknowledged
+    pub(super) fn pending(&self) -> bool {
+        self.required() && !self.acknowledged()
+    }
+
+    /// Require a new acknowledgement, when LightDM reuses the greeter for another login
+    pub(super) fn reset(&self) {
+        self.acknowledged.set(false);
+    }
+}
@@ -0,0 +1,122 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use ext::prelude::*;
+use serde::Serialize;
+
+use std::path::{Path, PathBuf};
+
+const POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+/// Battery status, read from sysfs
+pub(super) struct Battery {
+    device: Option<PathBuf>,
+    ac: Option<PathBuf>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, ToJSCValue)]
+pub(super) struct BatteryData {
+    name: String,
+    level: i32,
+    state: String,
+    ac_status: bool,
+    capacity: f64,
+    time: String,
+    watt: f64,
+}
+
+impl Battery {
+    pub(super) fn new(enabled: bool) -> Self {
+        if !enabled {
+            return Self {
+                device: None,
+                ac: None,
+            };
+        }
+
+        let supplies: Vec<PathBuf> = match std::fs::read_dir(POWER_SUPPLY_DIR) {
+            Ok(dir) => dir
+                .filter_map(|ent| ent.ok())
+                .map(|ent| ent.path())
+                .collect(),
+            Err(_) => vec![],
+        };
+        let of_type = |ty: &str| {
+            let mut found: Vec<&PathBuf> = supplies
+                .iter()
+                .filter(|path| read_string(&path.join("type")).as_deref() == Some(ty))
+                .collect();
+            found.sort();
+            found.first().map(|path| path.to_path_buf())
+        };
+
+        let device = of_type("Battery");
+        match &device {
+            Some(device) => logger_debug!("Battery device: {}", device.display()),
+            None => logger_warn!("No battery found in {POWER_SUPPLY_DIR}"),
+        }
+        Self {
+            device,
+            ac: of_type("Mains"),
+        }
+    }
+
+    pub(super) fn available(&self) -> bool {
+        self.device.is_some()
+    }
+
+    pub(super) fn data(&self) -> Option<BatteryData> {
+        let device = self.device.as_ref()?;
+        let name = device.file_name()?.to_string_lossy().to_string();
+        let level = read_number(&device.join("capacity")).unwrap_or(0.0) as i32;
+        let state = read_string(&device.join("status")).unwrap_or("Unknown".to_string());
+        let ac_status = self
+            .ac
+            .as_ref()
+            .and_then(|ac| read_number(&ac.join("online")))
+            .is_some_and(|online| online > 0.0);
+
+        // energy_* are in µWh and power_now in µW, charge_* in µAh and current_now in µA
+        let (now, full, rate) = match read_number(&device.join("energy_now")) {
+            Some(now) => (
+                now,
+                read_number(&device.join("energy_full")),
+                read_number(&device.join("power_now")),
+            ),
+            None => (
+                read_number(&device.join("charge_now")).unwrap_or(0.0),
+                read_number(&device.join("charge_full")),
+                read_number(&device.join("current_now")),
+            ),
+        };
+        let full = full.unwrap_or(0.0);
+        let rate = rate.unwrap_or(0.0);
+        let hours = match state.as_str() {
+            "Charging" if rate > 0.0 => (full - now) / rate,
+            "Discharging" if rate > 0.0 => now / rate,
+            _ => 0.0,
+        };
+        let minutes = (hours * 60.0).round() as i64;
+
+        Some(BatteryData {
+            name,
+            level,
+            state,
+            ac_status,
+            capacity: full / 1_000_000.0,
+            time: format!("{}:{:02}", minutes / 60, minutes % 60),
+            watt: rate / 1_000_000.0,
+        })
+    }
+}
+
+fn read_string(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+fn read_number(path: &Path) -> Option<f64> {
+    read_string(path)?.parse().ok()
+}
@@ -0,0 +1,321 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Failed authentications, counted by the greeter and read from the tally files of pam_faillock.
+//! Whether an account is locked follows the `deny`, `unlock_time` and `fail_interval` options of
+//! faillock.conf.
+
+use serde::Serialize;
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const FAILLOCK_CONF: &str = "/etc/security/faillock.conf";
+/// Size of a `struct tally`: source, reserved, status and time
+const TALLY_SIZE: usize = 64;
+const TALLY_STATUS_OFFSET: usize = 54;
+const TALLY_TIME_OFFSET: usize = 56;
+const TALLY_STATUS_VALID: u16 = 0x1;
+
+/// The options of pam_faillock deciding when an account is locked, in seconds
+struct Policy {
+    /// Failures locking the account, 0 if accounts are never locked
+    deny: u32,
+    /// Time after the last failure the account is unlocked, 0 if it stays locked
+    unlock_time: u64,
+    /// Failures older than this, before the last one, are not counted
+    fail_interval: u64,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            deny: 3,
+            unlock_time: 600,
+            fail_interval: 900,
+        }
+    }
+}
+
+impl Policy {
+    fn read(path: &Path) -> Self {
+        let mut policy = Self::default();
+        let Ok(content) = std::fs::read_to_string(path) else {
+            return policy;
+        };
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or_default();
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim() {
+                "deny" => policy.deny = value.parse().unwrap_or(policy.deny),
+                "unlock_time" if value == "never" => policy.unlock_time = 0,
+                "unlock_time" => policy.unlock_time = value.parse().unwrap_or(policy.unlock_time),
+                "fail_interval" => {
+                    policy.fail_interval = value.parse().unwrap_or(policy.fail_interval)
+                }
+                _ => {}
+            }
+        }
+        policy
+    }
+}
+
+/// Failed authentications of a user as reported by `lightdm.auth_failures`
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub(super) struct AuthFailures {
+    pub(super) failures: u32,
+    /// Failures left before the account is locked, `None` if accounts are never locked
+    pub(super) remaining: Option<u32>,
+    pub(super) locked: bool,
+    /// When the account is unlocked, in seconds since the epoch, `None` if it stays locked
+    pub(super) unlock_time: Option<u64>,
+}
+
+pub(super) struct Faillock {
+    dir: Option<PathBuf>,
+    policy: Policy,
+    /// Times of the failures seen by the greeter since the last successful authentication, used
+    /// when the tally of a user cannot be read
+    failures: RefCell<HashMap<String, Vec<u64>>>,
+    /// An unreadable tally was reported, it is not repeated for each query
+    warned: Cell<bool>,
+}
+
+impl Faillock {
+    pub(super) fn new(dir: &str) -> Self {
+        Self {
+            dir: (!dir.is_empty()).then(|| PathBuf::from(dir)),
+            policy: Policy::read(Path::new(FAILLOCK_CONF)),
+            failures: Default::default(),
+            warned: Cell::new(false),
+        }
+    }
+
+    /// Count an authentication of `username`, pam_faillock resets the tally when it succeeds
+    pub(super) fn record(&self, username: &str, authenticated: bool) {
+        let mut failures = self.failures.borrow_mut();
+        if authenticated {
+            failures.remove(username);
+        } else {
+            failures
+                .entry(username.to_string())
+                .or_default()
+                .push(now());
+        }
+    }
+
+    pub(super) fn failures(&self, username: &str) -> AuthFailures {
+        let times = self
+            .tally(username)
+            .or_else(|| self.failures.borrow().get(username).cloned())
+            .unwrap_or_default();
+        let Some(&latest) = times.iter().max() else {
+            return AuthFailures {
+                remaining: (self.policy.deny > 0).then_some(self.policy.deny),
+                ..Default::default()
+            };
+        };
+
+        let failures = times
+            .iter()
+            .filter(|&&time| latest - time <= self.policy.fail_interval)
+            .count() as u32;
+        if self.policy.deny == 0 {
+            return AuthFailures {
+                failures,
+                ..Default::default()
+            };
+        }
+
+        let unlock_time = (self.policy.unlock_time > 0).then(|| latest + self.policy.unlock_time);
+        let locked =
+            failures >= self.policy.deny && unlock_time.is_none_or(|unlock| unlock > now());
+        AuthFailures {
+            failures,
+            remaining: Some(self.policy.deny.saturating_sub(failures)),
+            locked,
+            unlock_time: unlock_time.filter(|_| locked),
+        }
+    }
+
+    /// Times of the valid records of the tally file of `username`, `None` if it cannot be read.
+    /// The file is missing until the first failure, or if pam_faillock is not used.
+    fn tally(&self, username: &str) -> Option<Vec<u64>> {
+        let path = self.dir.as_ref()?.join(username);
+        match std::fs::read(&path) {
+            Ok(content) => Some(parse_tally(&content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                if !self.warned.replace(true) {
+                    logger_warn!(
+                        "Could not read {}: {e}, only the failures seen by the greeter are counted. \
+                         The lightdm user needs read access to the tally files",
+                        path.display()
+                    );
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Times of the valid records of a tally file, a truncated last record is ignored
+fn parse_tally(content: &[u8]) -> Vec<u64> {
+    content
+        .chunks_exact(TALLY_SIZE)
+        .filter(|record| {
+            let status = &record[TALLY_STATUS_OFFSET..TALLY_TIME_OFFSET];
+            u16::from_ne_bytes([status[0], status[1]]) & TALLY_STATUS_VALID != 0
+        })
+        .map(|record| {
+            let time = &record[TALLY_TIME_OFFSET..TALLY_SIZE];
+            u64::from_ne_bytes(time.try_into().expect("8 bytes time"))
+        })
+        .collect()
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since| since.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A `struct tally` failed at `time` from `source`
+    fn record(source: &str, status: u16, time: u64) -> Vec<u8> {
+        let mut record = vec![0; TALLY_SIZE];
+        record[..source.len()].copy_from_slice(source.as_bytes());
+        record[TALLY_STATUS_OFFSET..TALLY_TIME_OFFSET].copy_from_slice(&status.to_ne_bytes());
+        record[TALLY_TIME_OFFSET..].copy_from_slice(&time.to_ne_bytes());
+        record
+    }
+
+    fn faillock(dir: Option<PathBuf>) -> Faillock {
+        Faillock {
+            dir,
+            policy: Policy::default(),
+            failures: Default::default(),
+            warned: Cell::new(false),
+        }
+    }
+
+    /// A directory holding the tally `content` of `username`
+    fn tally_dir(name: &str, username: &str, content: &[u8]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("faillock-{}-{name}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(username), content).unwrap();
+        dir
+    }
+
+    #[test]
+    fn parse_valid_records() {
+        let content = [
+            record("tty1", TALLY_STATUS_VALID, 1000),
+            record(":0", TALLY_STATUS_VALID | 0x4, 2000),
+        ]
+        .concat();
+        assert_eq!(parse_tally(&content), [1000, 2000]);
+    }
+
+    #[test]
+    fn parse_skips_invalid_records() {
+        let content = [
+            record("tty1", 0, 1000),
+            record("tty1", 0x2, 1500),
+            record("tty1", TALLY_STATUS_VALID, 2000),
+        ]
+        .concat();
+        assert_eq!(parse_tally(&content), [2000]);
+    }
+
+    #[test]
+    fn parse_truncated_file() {
+        assert!(parse_tally(&[]).is_empty());
+        assert!(parse_tally(&[0xff; TALLY_SIZE - 1]).is_empty());
+
+        let mut content = record("tty1", TALLY_STATUS_VALID, 1000);
+        content.extend_from_slice(&record("tty1", TALLY_STATUS_VALID, 2000)[..TALLY_SIZE / 2]);
+        assert_eq!(parse_tally(&content), [1000]);
+    }
+
+    #[test]
+    fn policy_from_config() {
+        let path = std::env::temp_dir().join(format!("faillock-{}.conf", std::process::id()));
+        std::fs::write(
+            &path,
+            "# comment\ndeny = 5\nunlock_time = never\nfail_interval=60 # trailing\nsilent\n",
+        )
+        .unwrap();
+        let policy = Policy::read(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(policy.deny, 5);
+        assert_eq!(policy.unlock_time, 0);
+        assert_eq!(policy.fail_interval, 60);
+    }
+
+    #[test]
+    fn tally_locks_the_account() {
+        let latest = now();
+        let content = [
+            // Outside of fail_interval
+            record("tty1", TALLY_STATUS_VALID, latest - 2000),
+            record("tty1", TALLY_STATUS_VALID, latest - 20),
+            record("tty1", TALLY_STATUS_VALID, latest - 10),
+            record("tty1", TALLY_STATUS_VALID, latest),
+        ]
+        .concat();
+        let dir = tally_dir("locked", "alice", &content);
+        let failures = faillock(Some(dir.clone())).failures("alice");
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            failures,
+            AuthFailures {
+                failures: 3,
+                remaining: Some(0),
+                locked: true,
+                unlock_time: Some(latest + 600),
+            }
+        );
+    }
+
+    #[test]
+    fn tally_expired_lock() {
+        let latest = now() - 700;
+        let content = [latest - 2, latest - 1, latest]
+            .map(|time| record("tty1", TALLY_STATUS_VALID, time))
+            .concat();
+        let dir = tally_dir("expired", "alice", &content);
+        let failures = faillock(Some(dir.clone())).failures("alice");
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(failures.failures, 3);
+        assert!(!failures.locked);
+        assert_eq!(failures.unlock_time, None);
+    }
+
+    #[test]
+    fn missing_tally_counts_the_session_failures() {
+        let faillock = faillock(Some(std::env::temp_dir().join("faillock-missing")));
+        assert_eq!(faillock.failures("bob").remaining, Some(3));
+
+        faillock.record("bob", false);
+        let failures = faillock.failures("bob");
+        assert_eq!(failures.failures, 1);
+        assert_eq!(failures.remaining, Some(2));
+        assert!(!failures.locked);
+
+        faillock.record("bob", true);
+        assert_eq!(faillock.failures("bob").failures, 0);
+    }
+}
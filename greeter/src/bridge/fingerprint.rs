@@ -0,0 +1,353 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use gtk::{
+    gio::{self, Cancellable, DBusCallFlags, DBusConnection, DBusSignalFlags},
+    glib::{self, Variant, VariantTy, prelude::*, variant::ObjectPath},
+};
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+};
+
+const FPRINTD_NAME: &str = "net.reactivated.Fprint";
+const MANAGER_PATH: &str = "/net/reactivated/Fprint/Manager";
+const MANAGER_INTERFACE: &str = "net.reactivated.Fprint.Manager";
+const DEVICE_INTERFACE: &str = "net.reactivated.Fprint.Device";
+const NO_ENROLLED_PRINTS: &str = "net.reactivated.Fprint.Error.NoEnrolledPrints";
+const CALL_TIMEOUT_MS: i32 = 2000;
+
+/// Fingerprint readers, through fprintd on the system bus.
+///
+/// `pam_fprintd` verifies the prints during the PAM conversation, fprintd reports the progress
+/// of the verification with `VerifyStatus` signals. The bus is the one `DBUS_SYSTEM_BUS_ADDRESS`
+/// points to, if set.
+///
+/// fprintd is only called asynchronously, so that a slow or missing daemon does not freeze the
+/// greeter: whether users have enrolled prints is answered from the last check.
+pub(super) struct Fingerprint {
+    connection: Option<DBusConnection>,
+    /// Whether each checked user has enrolled prints on the default reader
+    enrolled: Rc<RefCell<HashMap<String, bool>>>,
+    /// Users whose prints are being listed
+    pending: Rc<RefCell<HashSet<String>>>,
+    on_available: Rc<dyn Fn(&str, bool)>,
+}
+
+impl Fingerprint {
+    /// Connect to fprintd if `enabled`, `on_status` is called with the result of each scan,
+    /// without its `verify-` prefix, and whether the verification is done. `on_available` is
+    /// called when a check finds that a user can use a reader or no longer can
+    pub(super) fn new(
+        enabled: bool,
+        on_status: impl Fn(&str, bool) + 'static,
+        on_available: impl Fn(&str, bool) + 'static,
+    ) -> Self {
+        if !enabled {
+            return Self::with_connection(None, on_available);
+        }
+
+        let connection = match gio::bus_get_sync(gio::BusType::System, Cancellable::NONE) {
+            Ok(connection) => connection,
+            Err(e) => {
+                logger_warn!("Fingerprint readers are unavailable: {}", e.message());
+                return Self::with_connection(None, on_available);
+            }
+        };
+        connection.signal_subscribe(
+            Some(FPRINTD_NAME),
+            Some(DEVICE_INTERFACE),
+            Some("VerifyStatus"),
+            None,
+            None,
+            DBusSignalFlags::NONE,
+            move |_, _, _, _, _, parameters| match parameters.get::<(String, bool)>() {
+                Some((result, done)) => {
+                    on_status(result.trim_start_matches("verify-"), done);
+                }
+                None => logger_warn!("Unexpected fprintd VerifyStatus {parameters}"),
+            },
+        );
+        logger_debug!("Fingerprint readers enabled");
+        Self::with_connection(Some(connection), on_available)
+    }
+
+    fn with_connection(
+        connection: Option<DBusConnection>,
+        on_available: impl Fn(&str, bool) + 'static,
+    ) -> Self {
+        Self {
+            connection,
+            enrolled: Default::default(),
+            pending: Default::default(),
+            on_available: Rc::new(on_available),
+        }
+    }
+
+    /// Whether a reader is present and `username` has enrolled prints on it, `false` until the
+    /// first check of `username` is done. The check is redone in the background.
+    pub(super) fn available(&self, username: &str) -> bool {
+        self.check(username);
+        self.enrolled
+            .borrow()
+            .get(username)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Ask fprintd whether `username` has enrolled prints, unless it is already being asked
+    pub(super) fn check(&self, username: &str) {
+        let Some(connection) = &self.connection else {
+            return;
+        };
+        if !self.pending.borrow_mut().insert(username.to_string()) {
+            return;
+        }
+
+        let check = Check {
+            username: username.to_string(),
+            enrolled: self.enrolled.clone(),
+            pending: self.pending.clone(),
+            on_available: self.on_available.clone(),
+        };
+        let device_connection = connection.clone();
+        call(
+            connection,
+            MANAGER_PATH,
+            MANAGER_INTERFACE,
+            "GetDefaultDevice",
+            None,
+            "(o)",
+            move |reply| {
+                let device = match reply {
+                    Ok(reply) => reply.get::<(ObjectPath,)>().map(|(device,)| device),
+                    Err(e) => {
+                        logger_debug!("No fingerprint reader: {}", e.message());
+                        None
+                    }
+                };
+                let Some(device) = device else {
+                    check.finish(false);
+                    return;
+                };
+
+                let args = (check.username.as_str(),).to_variant();
+                call(
+                    &device_connection,
+                    device.as_str(),
+                    DEVICE_INTERFACE,
+                    "ListEnrolledFingers",
+                    Some(&args),
+                    "(as)",
+                    move |reply| {
+                        let available = match reply {
+                            Ok(reply) => reply
+                                .get::<(Vec<String>,)>()
+                                .is_some_and(|(fingers,)| !fingers.is_empty()),
+                            Err(e) => {
+                                if gio::DBusError::remote_error(&e).as_deref()
+                                    != Some(NO_ENROLLED_PRINTS)
+                                {
+                                    logger_warn!(
+                                        "Could not list the prints of {}: {}",
+                                        check.username,
+                                        e.message()
+                                    );
+                                }
+                                false
+                            }
+                        };
+                        check.finish(available);
+                    },
+                );
+            },
+        );
+    }
+}
+
+/// A check of the prints of a user in progress
+struct Check {
+    username: String,
+    enrolled: Rc<RefCell<HashMap<String, bool>>>,
+    pending: Rc<RefCell<HashSet<String>>>,
+    on_available: Rc<dyn Fn(&str, bool)>,
+}
+
+impl Check {
+    fn finish(self, available: bool) {
+        self.pending.borrow_mut().remove(&self.username);
+        let previous = self
+            .enrolled
+            .borrow_mut()
+            .insert(self.username.clone(), available)
+            .unwrap_or(false);
+        if previous != available {
+            (self.on_available)(&self.username, available);
+        }
+    }
+}
+
+fn call(
+    connection: &DBusConnection,
+    path: &str,
+    interface: &str,
+    method: &str,
+    args: Option<&Variant>,
+    reply: &str,
+    callback: impl FnOnce(Result<Variant, glib::Error>) + 'static,
+) {
+    connection.call(
+        Some(FPRINTD_NAME),
+        path,
+        interface,
+        method,
+        args,
+        Some(VariantTy::new(reply).expect("valid reply type")),
+        DBusCallFlags::NONE,
+        CALL_TIMEOUT_MS,
+        Cancellable::NONE,
+        callback,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use gtk::gio::{DBusConnectionFlags, DBusNodeInfo, TestDBus, TestDBusFlags};
+
+    use super::*;
+
+    const DEVICE_PATH: &str = "/net/reactivated/Fprint/Device/0";
+    const INTROSPECTION: &str = r#"
+<node>
+  <interface name="net.reactivated.Fprint.Manager">
+    <method name="GetDefaultDevice">
+      <arg type="o" name="device" direction="out"/>
+    </method>
+  </interface>
+  <interface name="net.reactivated.Fprint.Device">
+    <method name="ListEnrolledFingers">
+      <arg type="s" name="username" direction="in"/>
+      <arg type="as" name="enrolled_fingers" direction="out"/>
+    </method>
+  </interface>
+</node>"#;
+
+    fn connect(bus: &TestDBus) -> DBusConnection {
+        DBusConnection::for_address_sync(
+            &bus.bus_address().expect("test bus is up"),
+            DBusConnectionFlags::AUTHENTICATION_CLIENT
+                | DBusConnectionFlags::MESSAGE_BUS_CONNECTION,
+            None,
+            Cancellable::NONE,
+        )
+        .expect("connected to the test bus")
+    }
+
+    /// Serve a fprintd with one reader on which only alice enrolled prints
+    fn mock_fprintd(connection: &DBusConnection) {
+        let node = DBusNodeInfo::for_xml(INTROSPECTION).expect("valid introspection");
+        connection
+            .register_object(
+                MANAGER_PATH,
+                &node.lookup_interface(MANAGER_INTERFACE).unwrap(),
+            )
+            .method_call(|_, _, _, _, _, _, invocation| {
+                let device = ObjectPath::try_from(DEVICE_PATH).unwrap();
+                invocation.return_value(Some(&(device,).to_variant()));
+            })
+            .build()
+            .expect("manager registered");
+        connection
+            .register_object(
+                DEVICE_PATH,
+                &node.lookup_interface(DEVICE_INTERFACE).unwrap(),
+            )
+            .method_call(|_, _, _, _, _, parameters, invocation| {
+                match parameters.get::<(String,)>() {
+                    Some((username,)) if username == "alice" => {
+                        let fingers = vec!["right-index-finger".to_string()];
+                        invocation.return_value(Some(&(fingers,).to_variant()));
+                    }
+                    _ => invocation.return_dbus_error(NO_ENROLLED_PRINTS, "No prints"),
+                }
+            })
+            .build()
+            .expect("device registered");
+        connection
+            .call_sync(
+                Some("org.freedesktop.DBus"),
+                "/org/freedesktop/DBus",
+                "org.freedesktop.DBus",
+                "RequestName",
+                Some(&(FPRINTD_NAME, 0u32).to_variant()),
+                VariantTy::new("(u)").ok(),
+                DBusCallFlags::NONE,
+                -1,
+                Cancellable::NONE,
+            )
+            .expect("name acquired");
+    }
+
+    fn wait_for_checks(fingerprint: &Fingerprint) {
+        let context = glib::MainContext::default();
+        while !fingerprint.pending.borrow().is_empty() {
+            context.iteration(true);
+        }
+    }
+
+    #[test]
+    fn availability_is_checked_in_the_background() {
+        let bus = TestDBus::new(TestDBusFlags::NONE);
+        bus.up();
+        let service = connect(&bus);
+        mock_fprintd(&service);
+
+        let changes: Rc<RefCell<Vec<(String, bool)>>> = Default::default();
+        let fingerprint = Fingerprint::with_connection(Some(connect(&bus)), {
+            let changes = changes.clone();
+            move |username, available| changes.borrow_mut().push((username.to_string(), available))
+        });
+
+        // Nothing is known before fprintd answers
+        assert!(!fingerprint.available("alice"));
+        assert!(!fingerprint.available("bob"));
+        wait_for_checks(&fingerprint);
+        assert_eq!(*changes.borrow(), [("alice".to_string(), true)]);
+
+        assert!(fingerprint.available("alice"));
+        assert!(!fingerprint.available("bob"));
+        wait_for_checks(&fingerprint);
+        assert_eq!(changes.borrow().len(), 1);
+
+        bus.down();
+    }
+
+    #[test]
+    fn missing_fprintd_is_unavailable() {
+        let bus = TestDBus::new(TestDBusFlags::NONE);
+        bus.up();
+
+        let changes: Rc<RefCell<Vec<(String, bool)>>> = Default::default();
+        let fingerprint = Fingerprint::with_connection(Some(connect(&bus)), {
+            let changes = changes.clone();
+            move |username, available| changes.borrow_mut().push((username.to_string(), available))
+        });
+        assert!(!fingerprint.available("alice"));
+        wait_for_checks(&fingerprint);
+        assert!(!fingerprint.available("alice"));
+        assert!(changes.borrow().is_empty());
+
+        wait_for_checks(&fingerprint);
+        bus.down();
+    }
+
+    #[test]
+    fn disabled_is_unavailable() {
+        let fingerprint = Fingerprint::new(false, |_, _| {}, |_, _| unreachable!());
+        assert!(!fingerprint.available("alice"));
+        assert!(fingerprint.pending.borrow().is_empty());
+    }
+}
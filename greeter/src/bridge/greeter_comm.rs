@@ -2,11 +2,8 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use gtk::{
-    gdk::Rectangle,
-    gio::Cancellable,
-    glib::{Variant, variant::ToVariant},
-};
+use gtk::gdk::Rectangle;
+use protocol::{Kind, Request};
 use webkit::prelude::WebViewExt;
 
 use ext::prelude::*;
@@ -25,26 +22,14 @@ impl GreeterComm {
         Self { context, browsers }
     }
 
-    pub(super) fn handle(
-        &self,
-        name: &str,
-        json_params: &str,
-        props: &BrowserProperties,
-    ) -> Variant {
-        let context = &self.context;
-        let params = jsc::Value::from_json(context, json_params).to_vec();
-        let ret = if "window_metadata" == name && params.is_empty() {
-            self.window_metadata(props)
-        } else if "broadcast" == name && !params.is_empty() {
-            self.greeter_comm_broadcast_cb(&params)
-        } else {
-            jsc::Value::new_undefined(context)
-        };
-
-        if let Some(json) = ret.to_json(0) {
-            json.to_variant()
-        } else {
-            "undefined".to_variant()
+    pub(super) fn handle(&self, request: &Request, props: &BrowserProperties) -> jsc::Value {
+        match (request.kind, request.method.as_str()) {
+            (Kind::Get, "window_metadata") => self.window_metadata(props),
+            (Kind::Call, "broadcast") => self.greeter_comm_broadcast_cb(&request.args),
+            (kind, name) => {
+                logger_warn!("greeter_comm.{name} {kind} is not implemented");
+                jsc::Value::new_undefined(&self.context)
+            }
         }
     }
 
@@ -56,29 +41,38 @@ impl GreeterComm {
                 &secondary
             };
             let uri = "file://".to_string() + theme_file;
-            browser.webview().load_uri(&uri);
+            let webview = browser.webview();
+            let zoom_level = webview.zoom_level();
+            webview.load_uri(&uri);
+            webview.set_zoom_level(zoom_level);
         });
     }
 
-    fn greeter_comm_broadcast_cb(&self, args: &[jsc::Value]) -> jsc::Value {
-        let context = &self.context;
+    pub(super) fn reload_theme<F>(&self, filter: F)
+    where
+        F: Fn(&Browser) -> bool,
+    {
         self.browsers
             .iter()
-            .map(|b| b.webview())
-            .for_each(|webview| {
-                let parameters = [
-                    "_emit",
-                    jsc::Value::new_array_from_garray(context, args)
-                        .to_json(0)
-                        .expect("greeter_comm._emit paramerter errors")
-                        .as_str(),
-                ]
-                .to_variant();
-                let message = webkit::UserMessage::new("greeter_comm", Some(&parameters));
-                webview.send_message_to_page(&message, Cancellable::NONE, |_| {});
+            .filter(|browser| filter(browser))
+            .for_each(|browser| {
+                let webview = browser.webview();
+                let zoom_level = webview.zoom_level();
+                webview.reload();
+                webview.set_zoom_level(zoom_level);
             });
+    }
+
+    fn greeter_comm_broadcast_cb(&self, args: &[serde_json::Value]) -> jsc::Value {
+        let data = args.first().cloned().unwrap_or_default();
+        super::emit(
+            &self.browsers,
+            "greeter_comm",
+            "broadcast_received",
+            vec![data],
+        );
 
-        jsc::Value::new_null(context)
+        jsc::Value::new_null(&self.context)
     }
 
     fn window_metadata(&self, props: &BrowserProperties) -> jsc::Value {
@@ -2,18 +2,54 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use gtk::glib::{Variant, variant::ToVariant};
 use lightdm::prelude::*;
+use protocol::{Kind, Request};
+use serde::Serialize;
 
 use ext::prelude::*;
 
-use std::ops::{Deref, DerefMut};
+use std::{
+    ops::{Deref, DerefMut},
+    path::Path,
+    rc::Rc,
+};
+
+use super::banner::BannerState;
+use crate::{
+    browser::Browser,
+    settings::{Settings, Theme},
+};
+
+/// Theme API version and features, `greeter_config.api`
+#[derive(Serialize)]
+struct Api {
+    version: &'static str,
+    greeter_version: &'static str,
+    build: Build,
+    backend: &'static str,
+    features: Vec<&'static str>,
+}
+
+#[derive(Serialize)]
+struct Build {
+    profile: &'static str,
+    arch: &'static str,
+    os: &'static str,
+}
 
-use crate::settings::Settings;
+/// The login banner, `greeter_config.banner`
+#[derive(Serialize)]
+struct Banner {
+    text: Option<String>,
+    format: &'static str,
+    acknowledgement_required: bool,
+    acknowledged: bool,
+}
 
 pub(super) struct GreeterConfig {
     context: jsc::Context,
     config: Settings,
+    banner: Rc<BannerState>,
 }
 
 impl Deref for GreeterConfig {
@@ -31,113 +67,117 @@ impl DerefMut for GreeterConfig {
 }
 
 impl GreeterConfig {
-    pub(super) fn new(context: jsc::Context, config: Settings) -> Self {
-        Self { context, config }
+    pub(super) fn new(context: jsc::Context, config: Settings, banner: Rc<BannerState>) -> Self {
+        Self {
+            context,
+            config,
+            banner,
+        }
     }
 
-    pub(super) fn handle(&self, name: &str) -> Variant {
-        let context = &self.context;
-        let ret = match name {
-            "branding" => self.branding(),
-            "greeter" => self.greeter(),
-            "features" => self.features(),
-            "layouts" => self.layouts(),
-            _ => jsc::Value::new_undefined(context),
+    pub(super) fn handle(&self, request: &Request) -> jsc::Value {
+        match (request.kind, request.method.as_str()) {
+            (Kind::Get, "api") => self.api(),
+            (Kind::Get, "banner") => self.banner(),
+            (Kind::Get, "branding") => self.branding(),
+            (Kind::Get, "greeter") => self.greeter(),
+            (Kind::Get, "features") => self.features(),
+            (Kind::Get, "theme") => self.theme_manifest(),
+            (Kind::Get, "layouts") => self.layouts(),
+            (Kind::Call, "acknowledge_banner") => self.acknowledge_banner(),
+            (kind, name) => {
+                logger_warn!("greeter_config.{name} {kind} is not implemented");
+                jsc::Value::new_undefined(&self.context)
+            }
+        }
+    }
+
+    fn api(&self) -> jsc::Value {
+        let features = [
+            ("battery", self.battery()),
+            ("backlight", self.backlight_enabled()),
+            ("fingerprint", self.fingerprint()),
+        ];
+        let api = Api {
+            version: protocol::API_VERSION,
+            greeter_version: env!("CARGO_PKG_VERSION"),
+            build: Build {
+                profile: if cfg!(debug_assertions) {
+                    "debug"
+                } else {
+                    "release"
+                },
+                arch: std::env::consts::ARCH,
+                os: std::env::consts::OS,
+            },
+            backend: "lightdm",
+            features: features
+                .into_iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(feature, _)| feature)
+                .collect(),
+        };
+        self.serialize("api", &api)
+    }
+
+    fn banner(&self) -> jsc::Value {
+        let file = self.banner_file();
+        let text = match file {
+            "" => None,
+            file => match std::fs::read_to_string(file) {
+                Ok(text) => Some(text),
+                Err(e) => {
+                    logger_error!("Could not read the banner {file}: {e}");
+                    None
+                }
+            },
+        };
+        let markdown = Path::new(file)
+            .extension()
+            .is_some_and(|ext| ext == "md" || ext == "markdown");
+        let banner = Banner {
+            text,
+            format: if markdown { "markdown" } else { "text" },
+            acknowledgement_required: self.banner.required(),
+            acknowledged: self.banner.acknowledged(),
         };
+        self.serialize("banner", &banner)
+    }
+
+    fn acknowledge_banner(&self) -> jsc::Value {
+        let file = self.banner_file();
+        if file.is_empty() {
+            logger_warn!("greeter_config.acknowledge_banner: there is no banner");
+            return jsc::Value::new_boolean(&self.context, false);
+        }
 
-        if let Some(json) = ret.to_json(0) {
-            json.to_variant()
-        } else {
-            "undefined".to_variant()
+        if !self.banner.acknowledged() {
+            self.banner.acknowledge();
+            logger_info!("Login banner {file} acknowledged");
         }
+        jsc::Value::new_boolean(&self.context, true)
     }
 
     fn branding(&self) -> jsc::Value {
-        let images_dir = self.branding_background_images_dir();
-        let logo_image = self.branding_logo_image();
-        let user_image = self.branding_user_image();
-
-        let context = &self.context;
-        let value = jsc::Value::new_object(context, None, None);
-        value.object_set_property(
-            "background_images_dir",
-            &jsc::Value::new_string(context, Some(images_dir)),
-        );
-        value.object_set_property(
-            "logo_image",
-            &jsc::Value::new_string(context, Some(logo_image)),
-        );
-        value.object_set_property(
-            "user_image",
-            &jsc::Value::new_string(context, Some(user_image)),
-        );
-
-        value
+        self.serialize("branding", self.branding_section())
     }
 
     fn greeter(&self) -> jsc::Value {
-        let debug_mode = self.debug_mode();
-        let detect_theme_errors = self.detect_theme_errors();
-        let screensaver_timeout = self.screensaver_timeout();
-        let secure_mode = self.secure_mode();
-        let theme = self.theme();
-        let icon_theme = self.icon_theme();
-        let time_language = self.time_language();
-
-        let context = &self.context;
-        let value = jsc::Value::new_object(context, None, None);
-        value.object_set_property("debug_mode", &jsc::Value::new_boolean(context, debug_mode));
-        value.object_set_property(
-            "detect_theme_errors",
-            &jsc::Value::new_boolean(context, detect_theme_errors),
-        );
-        value.object_set_property(
-            "screensaver_timeout",
-            &jsc::Value::new_number(context, screensaver_timeout as f64),
-        );
-        value.object_set_property(
-            "secure_mode",
-            &jsc::Value::new_boolean(context, secure_mode),
-        );
-        value.object_set_property("theme", &jsc::Value::new_string(context, Some(theme)));
-        value.object_set_property(
-            "icon_theme",
-            &jsc::Value::new_string(context, Some(icon_theme)),
-        );
-        value.object_set_property(
-            "time_language",
-            &jsc::Value::new_string(context, Some(time_language)),
-        );
-
-        value
+        self.serialize("greeter", self.greeter_section())
     }
 
     fn features(&self) -> jsc::Value {
-        let battery = self.battery();
-        let backlight_enabled = self.backlight_enabled();
-        let backlight_value = self.backlight_value();
-        let backlight_steps = self.backlight_steps();
+        self.serialize("features", self.features_section())
+    }
 
-        let context = &self.context;
-        let backlight = jsc::Value::new_object(context, None, None);
-        backlight.object_set_property(
-            "enabled",
-            &jsc::Value::new_boolean(context, backlight_enabled),
-        );
-        backlight.object_set_property(
-            "value",
-            &jsc::Value::new_number(context, backlight_value as f64),
-        );
-        backlight.object_set_property(
-            "steps",
-            &jsc::Value::new_number(context, backlight_steps as f64),
-        );
-
-        let value = jsc::Value::new_object(context, None, None);
-        value.object_set_property("battery", &jsc::Value::new_boolean(context, battery));
-        value.object_set_property("backlight", &backlight);
-
-        value
+    fn theme_manifest(&self) -> jsc::Value {
+        let theme = Theme {
+            primary_html: self.primary_html(),
+            secondary_html: self.secondary_html(),
+            legacy_api: self.legacy_api(),
+            min_api_version: self.min_api_version().map(str::to_string),
+        };
+        self.serialize("theme", &theme)
     }
 
     fn layouts(&self) -> jsc::Value {
@@ -156,4 +196,15 @@ impl GreeterConfig {
 
         jsc::Value::new_array_from_garray(context, &vals)
     }
+
+    fn serialize<T: Serialize>(&self, name: &str, value: &T) -> jsc::Value {
+        ext::to_jscvalue(&self.context, value).unwrap_or_else(|e| {
+            logger_error!("Could not convert greeter_config.{name}: {e}");
+            jsc::Value::new_undefined(&self.context)
+        })
+    }
+}
+
+pub(super) fn config_changed(browsers: &[Browser]) {
+    super::emit(browsers, "greeter_config", "config_changed", vec![]);
 }
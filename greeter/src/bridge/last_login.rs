@@ -0,0 +1,194 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Last login times, from the `utmp` records of wtmp and the per-UID records of lastlog, in the
+//! glibc layout of 64-bit Linux. Systems logging to wtmpdb or lastlog2 instead are not supported.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs::File,
+    io::{BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+pub(super) const WTMP: &str = "/var/log/wtmp";
+pub(super) const LASTLOG: &str = "/var/log/lastlog";
+
+/// Size of a `struct utmp`
+const UTMP_SIZE: usize = 384;
+const UT_USER: std::ops::Range<usize> = 44..76;
+const UT_TV_SEC: std::ops::Range<usize> = 340..344;
+const USER_PROCESS: i16 = 7;
+/// Size of a `struct lastlog`: time, line and host
+const LASTLOG_SIZE: u64 = 292;
+
+/// Last login times of users, in seconds since the epoch. wtmp is read at once, lastlog as
+/// users are looked up
+pub(super) struct LastLogins {
+    wtmp: HashMap<String, u64>,
+    lastlog: PathBuf,
+    lastlog_times: RefCell<HashMap<u32, Option<u64>>>,
+}
+
+impl LastLogins {
+    pub(super) fn read(wtmp: &Path, lastlog: &Path) -> Self {
+        let wtmp = match File::open(wtmp) {
+            Ok(file) => read_wtmp(BufReader::new(file)),
+            Err(e) => {
+                logger_debug!("Could not read {}: {e}", wtmp.display());
+                HashMap::new()
+            }
+        };
+        Self {
+            wtmp,
+            lastlog: lastlog.to_path_buf(),
+            lastlog_times: Default::default(),
+        }
+    }
+
+    pub(super) fn get(&self, username: &str, uid: u32) -> Option<u64> {
+        let wtmp = self.wtmp.get(username).copied();
+        let lastlog = *self
+            .lastlog_times
+            .borrow_mut()
+            .entry(uid)
+            .or_insert_with(|| {
+                File::open(&self.lastlog)
+                    .ok()
+                    .and_then(|file| read_lastlog(file, uid))
+            });
+        wtmp.max(lastlog)
+    }
+}
+
+/// The latest login of each user, wtmp is in chronological order
+fn read_wtmp(mut reader: impl Read) -> HashMap<String, u64> {
+    let mut logins = HashMap::new();
+    let mut record = [0; UTMP_SIZE];
+    while reader.read_exact(&mut record).is_ok() {
+        if i16::from_ne_bytes([record[0], record[1]]) != USER_PROCESS {
+            continue;
+        }
+        let Some(username) = c_string(&record[UT_USER]) else {
+            continue;
+        };
+        let time = i32::from_ne_bytes(record[UT_TV_SEC].try_into().expect("4 bytes time"));
+        logins.insert(username, time.max(0) as u64);
+    }
+    logins
+}
+
+/// The lastlog record of `uid`, the file is sparse and indexed by UID
+fn read_lastlog(mut file: impl Read + Seek, uid: u32) -> Option<u64> {
+    let mut time = [0; 4];
+    file.seek(SeekFrom::Start(uid as u64 * LASTLOG_SIZE)).ok()?;
+    file.read_exact(&mut time).ok()?;
+    let time = i32::from_ne_bytes(time);
+    (time > 0).then_some(time as u64)
+}
+
+/// A NUL padded string, `None` if empty
+fn c_string(bytes: &[u8]) -> Option<String> {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    (end > 0).then(|| String::from_utf8_lossy(&bytes[..end]).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    const DEAD_PROCESS: i16 = 8;
+
+    /// A `struct utmp` of type `ty` for `user` at `time`
+    fn utmp(ty: i16, user: &str, time: i32) -> Vec<u8> {
+        let mut record = vec![0; UTMP_SIZE];
+        record[..2].copy_from_slice(&ty.to_ne_bytes());
+        record[UT_USER.start..UT_USER.start + user.len()].copy_from_slice(user.as_bytes());
+        record[UT_TV_SEC].copy_from_slice(&time.to_ne_bytes());
+        record
+    }
+
+    /// A lastlog file with the login `time` of each UID
+    fn lastlog(times: &[(u32, i32)]) -> Vec<u8> {
+        let size = times.iter().map(|&(uid, _)| uid + 1).max().unwrap_or(0) as usize;
+        let mut content = vec![0; size * LASTLOG_SIZE as usize];
+        for &(uid, time) in times {
+            let offset = uid as usize * LASTLOG_SIZE as usize;
+            content[offset..offset + 4].copy_from_slice(&time.to_ne_bytes());
+        }
+        content
+    }
+
+    #[test]
+    fn wtmp_latest_login_of_each_user() {
+        let content = [
+            utmp(USER_PROCESS, "alice", 1000),
+            utmp(USER_PROCESS, "bob", 1500),
+            utmp(USER_PROCESS, "alice", 2000),
+        ]
+        .concat();
+        let logins = read_wtmp(Cursor::new(content));
+        assert_eq!(logins.len(), 2);
+        assert_eq!(logins["alice"], 2000);
+        assert_eq!(logins["bob"], 1500);
+    }
+
+    #[test]
+    fn wtmp_user_field_spans_32_bytes() {
+        let user = "a".repeat(UT_USER.len());
+        let content = utmp(USER_PROCESS, &user, 1000);
+        assert_eq!(read_wtmp(Cursor::new(content))[&user], 1000);
+    }
+
+    #[test]
+    fn wtmp_skips_other_records() {
+        let content = [
+            utmp(DEAD_PROCESS, "alice", 1000),
+            utmp(USER_PROCESS, "", 1500),
+            utmp(USER_PROCESS, "bob", -1),
+        ]
+        .concat();
+        let logins = read_wtmp(Cursor::new(content));
+        assert!(!logins.contains_key("alice"));
+        assert_eq!(logins.len(), 1);
+        assert_eq!(logins["bob"], 0);
+    }
+
+    #[test]
+    fn wtmp_short_or_corrupt_file() {
+        assert!(read_wtmp(Cursor::new(vec![])).is_empty());
+        assert!(read_wtmp(Cursor::new(vec![0xff; UTMP_SIZE - 1])).is_empty());
+        assert!(read_wtmp(Cursor::new(vec![0xff; UTMP_SIZE])).is_empty());
+
+        let mut content = utmp(USER_PROCESS, "alice", 1000);
+        content.extend_from_slice(&utmp(USER_PROCESS, "bob", 2000)[..UTMP_SIZE / 2]);
+        let logins = read_wtmp(Cursor::new(content));
+        assert_eq!(logins.len(), 1);
+        assert_eq!(logins["alice"], 1000);
+    }
+
+    #[test]
+    fn lastlog_record_of_uid() {
+        let content = lastlog(&[(0, 500), (3, 1000)]);
+        assert_eq!(read_lastlog(Cursor::new(&content), 0), Some(500));
+        assert_eq!(read_lastlog(Cursor::new(&content), 3), Some(1000));
+        // Never logged in
+        assert_eq!(read_lastlog(Cursor::new(&content), 1), None);
+    }
+
+    #[test]
+    fn lastlog_short_or_corrupt_file() {
+        assert_eq!(read_lastlog(Cursor::new(vec![]), 0), None);
+        // Beyond the end of the file
+        assert_eq!(read_lastlog(Cursor::new(lastlog(&[(1, 1000)])), 5), None);
+        // Truncated in the middle of the time
+        let content = lastlog(&[(2, 1000)]);
+        let truncated = &content[..2 * LASTLOG_SIZE as usize + 2];
+        assert_eq!(read_lastlog(Cursor::new(truncated), 2), None);
+        assert_eq!(read_lastlog(Cursor::new(lastlog(&[(0, -5)])), 0), None);
+    }
+}
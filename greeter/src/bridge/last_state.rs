@@ -0,0 +1,232 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! The last successful login and the session, language and layout of each user, remembered
+//! across boots in the data directory of the lightdm user.
+
+use gtk::glib;
+use serde::{Deserialize, Serialize};
+
+use std::{
+    cell::{Ref, RefCell},
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    os::unix::fs::OpenOptionsExt,
+    path::{Path, PathBuf},
+};
+
+const STATE_VERSION: u32 = 1;
+const STATE_FILE: &str = "web-greeter/state.json";
+
+/// What a user last logged in with
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct UserState {
+    pub(super) username: String,
+    pub(super) session: Option<String>,
+    pub(super) language: Option<String>,
+    pub(super) layout: Option<String>,
+}
+
+/// The state reported by `lightdm.last_state`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(super) struct LastState {
+    version: u32,
+    /// The last user who logged in
+    pub(super) user: Option<String>,
+    pub(super) users: Vec<UserState>,
+}
+
+impl Default for LastState {
+    fn default() -> Self {
+        Self {
+            version: STATE_VERSION,
+            user: None,
+            users: vec![],
+        }
+    }
+}
+
+impl LastState {
+    pub(super) fn get(&self, username: &str) -> Option<&UserState> {
+        self.users.iter().find(|user| user.username == username)
+    }
+
+    fn record(&mut self, user: UserState, known_users: &[String]) {
+        self.user = Some(user.username.clone());
+        self.users.retain(|u| {
+            u.username != user.username
+                && (known_users.is_empty() || known_users.contains(&u.username))
+        });
+        self.users.push(user);
+    }
+
+    /// Reject states of another version, drop empty values and duplicated users
+    fn validate(mut self) -> Result<Self, String> {
+        if self.version != STATE_VERSION {
+            return Err(format!("unsupported version {}", self.version));
+        }
+
+        let non_empty = |value: &mut Option<String>| {
+            if value.as_ref().is_some_and(|value| value.trim().is_empty()) {
+                *value = None;
+            }
+        };
+        non_empty(&mut self.user);
+        let mut users: Vec<UserState> = vec![];
+        for mut user in self.users {
+            if user.username.trim().is_empty() || users.iter().any(|u| u.username == user.username)
+            {
+                continue;
+            }
+            non_empty(&mut user.session);
+            non_empty(&mut user.language);
+            non_empty(&mut user.layout);
+            users.push(user);
+        }
+        self.users = users;
+        Ok(self)
+    }
+}
+
+pub(super) struct StateStore {
+    path: PathBuf,
+    state: RefCell<LastState>,
+}
+
+impl StateStore {
+    pub(super) fn load() -> Self {
+        let path = glib::user_data_dir().join(STATE_FILE);
+        let state = match read(&path) {
+            Ok(state) => state,
+            Err(e) => {
+                logger_warn!("Ignoring the greeter state {}: {e}", path.display());
+                LastState::default()
+            }
+        };
+        Self {
+            path,
+            state: RefCell::new(state),
+        }
+    }
+
+    pub(super) fn state(&self) -> Ref<'_, LastState> {
+        self.state.borrow()
+    }
+
+    /// Remember a successful login of `user`, forgetting the users which are not among
+    /// `known_users` anymore. Nobody is forgotten if LightDM lists no users.
+    pub(super) fn record(&self, user: UserState, known_users: &[String]) {
+        self.state.borrow_mut().record(user, known_users);
+
+        let json =
+            serde_json::to_string_pretty(&*self.state.borrow()).expect("state is serializable");
+        if let Err(e) = write_atomically(&self.path, json.as_bytes()) {
+            logger_error!(
+                "Could not save the greeter state {}: {e}",
+                self.path.display()
+            );
+        }
+    }
+}
+
+/// The saved state, the default one if there is none yet
+fn read(path: &Path) -> Result<LastState, String> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(LastState::default()),
+        Err(e) => return Err(e.to_string()),
+    };
+    serde_json::from_str::<LastState>(&content)
+        .map_err(|e| e.to_string())?
+        .validate()
+}
+
+/// Write to a temporary file next to `path` then rename it, so that `path` is either the old or
+/// the new content even if the greeter is killed meanwhile
+fn write_atomically(path: &Path, content: &[u8]) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let temporary = path.with_extension("json.tmp");
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(&temporary)?;
+    file.write_all(content)?;
+    file.sync_all()?;
+    fs::rename(&temporary, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user(username: &str, session: Option<&str>) -> UserState {
+        UserState {
+            username: username.to_string(),
+            session: session.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn known(users: &[&str]) -> Vec<String> {
+        users.iter().map(|user| user.to_string()).collect()
+    }
+
+    #[test]
+    fn record_replaces_the_user() {
+        let mut state = LastState::default();
+        state.record(user("alice", Some("gnome")), &known(&["alice", "bob"]));
+        state.record(user("bob", None), &known(&["alice", "bob"]));
+        state.record(user("alice", Some("plasma")), &known(&["alice", "bob"]));
+        assert_eq!(state.user.as_deref(), Some("alice"));
+        assert_eq!(
+            state.users,
+            [user("bob", None), user("alice", Some("plasma"))]
+        );
+    }
+
+    #[test]
+    fn record_forgets_removed_users() {
+        let mut state = LastState::default();
+        state.record(user("alice", None), &known(&["alice", "bob"]));
+        state.record(user("bob", None), &known(&["alice", "bob"]));
+        state.record(user("carol", None), &known(&["bob", "carol"]));
+        assert_eq!(state.users, [user("bob", None), user("carol", None)]);
+        assert!(state.get("alice").is_none());
+
+        // Without a user list nobody is forgotten
+        state.record(user("dave", None), &[]);
+        assert_eq!(state.users.len(), 3);
+    }
+
+    #[test]
+    fn validate_cleans_the_state() {
+        let state = LastState {
+            version: STATE_VERSION,
+            user: Some(" ".to_string()),
+            users: vec![
+                user("alice", Some("")),
+                user("", Some("gnome")),
+                user("alice", Some("plasma")),
+            ],
+        };
+        let state = state.validate().unwrap();
+        assert_eq!(state.user, None);
+        assert_eq!(state.users, [user("alice", None)]);
+    }
+
+    #[test]
+    fn validate_rejects_other_versions() {
+        let state = LastState {
+            version: STATE_VERSION + 1,
+            ..Default::default()
+        };
+        assert!(state.validate().is_err());
+    }
+}
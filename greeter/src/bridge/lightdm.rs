@@ -2,32 +2,115 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use gtk::glib::{self, Variant, clone, variant::ToVariant};
+use gtk::{
+    gio::Cancellable,
+    glib::{self, ControlFlow, clone, translate::IntoGlib},
+};
 use lightdm::prelude::*;
+use protocol::{Kind, Request};
+use serde::Deserialize;
 
 use ext::prelude::*;
 
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, path::Path, rc::Rc};
 
-use crate::browser::Browser;
+use super::{
+    auth::{AuthStatus, Transition},
+    backlight::Backlight,
+    banner::BannerState,
+    battery::{Battery, BatteryData},
+    faillock::Faillock,
+    fingerprint::Fingerprint,
+    last_login::{self, LastLogins},
+    last_state::{StateStore, UserState},
+    pam::{self, PromptPurpose},
+    sessions::{self, SessionInfo, SessionValue},
+    users::{self, ListedUser, UserFilter},
+};
+use crate::{
+    browser::{self, Browser},
+    settings::{Sessions, Settings, Users},
+};
+
+const BATTERY_POLL_INTERVAL: u32 = 5;
 
 pub(super) struct LightDM {
     context: jsc::Context,
     greeter: lightdm::Greeter,
     user_list: Option<lightdm::UserList>,
+    users: RefCell<Users>,
+    sessions: RefCell<Sessions>,
     shared_data_directory: String,
-    brightness: RefCell<i32>,
+    backlight: Backlight,
+    backlight_value: i32,
+    battery: Rc<Battery>,
+    fingerprint: Fingerprint,
+    faillock: Rc<Faillock>,
+    banner: Rc<BannerState>,
+    state: Rc<StateStore>,
+    /// Read on the first fetch of the users, until one is added or removed
+    last_logins: Rc<RefCell<Option<Rc<LastLogins>>>>,
+    /// The language set for the user being authenticated, by username
+    language: RefCell<Option<(String, String)>>,
+    auth: Rc<RefCell<AuthStatus>>,
+    session_fade_out: u32,
+    browsers: Rc<Vec<Browser>>,
+}
+
+/// The part of a `LightDMLayout` needed to select it
+#[derive(Deserialize)]
+struct LayoutArg {
+    name: String,
 }
 
 impl LightDM {
-    pub(super) fn new(context: jsc::Context, browsers: Rc<Vec<Browser>>) -> Self {
+    pub(super) fn new(
+        context: jsc::Context,
+        browsers: Rc<Vec<Browser>>,
+        config: &Settings,
+        banner: Rc<BannerState>,
+    ) -> Self {
         let greeter = lightdm::Greeter::new();
         let user_list = lightdm::UserList::instance();
+        let backlight = Backlight::new(config.backlight_enabled(), config.backlight_steps());
+        let battery = Rc::new(Battery::new(config.battery()));
+        let auth = Rc::new(RefCell::new(AuthStatus::default()));
+        let faillock = Rc::new(Faillock::new(config.faillock_dir()));
+        let fingerprint = Fingerprint::new(
+            config.fingerprint(),
+            clone!(
+                #[weak]
+                browsers,
+                move |result, done| greeter::fingerprint_status(&browsers, result, done)
+            ),
+            clone!(
+                #[weak]
+                browsers,
+                move |username, available| {
+                    greeter::fingerprint_available_changed(&browsers, username, available)
+                }
+            ),
+        );
 
         greeter.connect_authentication_complete(clone!(
             #[weak]
             browsers,
-            move |_| greeter::authentication_complete(&browsers)
+            #[strong]
+            auth,
+            #[strong]
+            faillock,
+            move |greeter| {
+                let authenticated = greeter.is_authenticated();
+                if let Some(username) = greeter.authentication_user() {
+                    faillock.record(&username, authenticated);
+                    let failures = faillock.failures(&username);
+                    if failures.locked {
+                        greeter::user_locked(&browsers, &username, failures.unlock_time);
+                    }
+                }
+                report(&browsers, &auth, Transition::Complete { authenticated });
+                greeter::authentication_complete(&browsers)
+            }
         ));
         greeter.connect_autologin_timer_expired(clone!(
             #[weak]
@@ -35,20 +118,104 @@ impl LightDM {
             move |_| greeter::autologin_timer_expired(&browsers)
         ));
         greeter.connect_show_prompt(clone!(
-            #[weak]
-            context,
             #[weak]
             browsers,
-            move |_, text, ty| greeter::show_prompt(&browsers, &context, text, ty)
+            #[strong]
+            auth,
+            move |_, text, ty| {
+                let purpose = pam::prompt_purpose(text, auth.borrow().password_change);
+                let transition = Transition::Prompt {
+                    ty: ty.into_glib(),
+                    purpose,
+                };
+                report(&browsers, &auth, transition);
+                greeter::show_prompt(&browsers, text, ty, purpose)
+            }
         ));
         greeter.connect_show_message(clone!(
             #[weak]
-            context,
+            browsers,
+            #[strong]
+            auth,
+            move |_, text, ty| {
+                let transition = Transition::Message {
+                    ty: ty.into_glib(),
+                    password_change: pam::requires_password_change(text),
+                };
+                report(&browsers, &auth, transition);
+                greeter::show_message(&browsers, text, ty)
+            }
+        ));
+        let last_logins: Rc<RefCell<Option<Rc<LastLogins>>>> = Default::default();
+        greeter.connect_reset(clone!(
             #[weak]
             browsers,
-            move |_, text, ty| greeter::show_message(&browsers, &context, text, ty)
+            #[strong]
+            auth,
+            #[strong]
+            banner,
+            #[strong]
+            last_logins,
+            move |_| {
+                banner.reset();
+                // A user logged in since the greeter was reused
+                last_logins.replace(None);
+                report(&browsers, &auth, Transition::Reset);
+                greeter::reset(&browsers)
+            }
         ));
 
+        if let Some(user_list) = &user_list {
+            user_list.connect_user_added(clone!(
+                #[weak]
+                browsers,
+                #[strong]
+                last_logins,
+                move |_, _| {
+                    last_logins.replace(None);
+                    greeter::users_changed(&browsers)
+                }
+            ));
+            user_list.connect_user_changed(clone!(
+                #[weak]
+                browsers,
+                move |_, _| greeter::users_changed(&browsers)
+            ));
+            user_list.connect_user_removed(clone!(
+                #[weak]
+                browsers,
+                #[strong]
+                last_logins,
+                move |_, _| {
+                    last_logins.replace(None);
+                    greeter::users_changed(&browsers)
+                }
+            ));
+        }
+
+        if battery.available() {
+            let last = RefCell::new(battery.data());
+            glib::timeout_add_seconds_local(
+                BATTERY_POLL_INTERVAL,
+                clone!(
+                    #[weak]
+                    browsers,
+                    #[weak]
+                    battery,
+                    #[upgrade_or]
+                    ControlFlow::Break,
+                    move || {
+                        let data = battery.data();
+                        if *last.borrow() != data {
+                            greeter::battery_update(&browsers, data.as_ref());
+                            last.replace(data);
+                        }
+                        ControlFlow::Continue
+                    }
+                ),
+            );
+        }
+
         if let Err(e) = greeter.connect_to_daemon_sync() {
             logger_error!("{}", e.message());
         }
@@ -74,87 +241,143 @@ impl LightDM {
             None => "".to_string(),
         };
 
+        // Check the listed users now, so that the first answers of fingerprint_available are right
+        if let Some(user_list) = &user_list {
+            for name in user_list.users().iter().filter_map(|user| user.name()) {
+                fingerprint.check(&name);
+            }
+        }
+
         logger_debug!("LightDM API connected");
         Self {
             context,
             greeter,
             user_list,
+            users: RefCell::new(config.users().clone()),
+            sessions: RefCell::new(config.sessions().clone()),
             shared_data_directory,
-            brightness: RefCell::new(85),
+            backlight,
+            backlight_value: config.backlight_value(),
+            battery,
+            fingerprint,
+            faillock,
+            banner,
+            state: Rc::new(StateStore::load()),
+            last_logins,
+            language: RefCell::new(None),
+            auth,
+            session_fade_out: config.session_fade_out(),
+            browsers,
         }
     }
 
+    /// Follow a reloaded configuration, the cached `users` and `sessions` of the theme API are
+    /// invalidated by `config_changed`
+    pub(super) fn reload_configuration(&self, config: &Settings) {
+        self.users.replace(config.users().clone());
+        self.sessions.replace(config.sessions().clone());
+    }
+
     pub(super) fn shared_data_directory(&self) -> &str {
         &self.shared_data_directory
     }
 
-    pub(super) fn handle(&self, name: &str, json_params: &str) -> Variant {
+    pub(super) fn handle(&self, request: &Request) -> jsc::Value {
         let context = &self.context;
-        let params = jsc::Value::from_json(context, json_params).to_vec();
-        let ret = if params.is_empty() {
-            match name {
-                "authentication_user" => self.authentication_user(),
-                "autologin_guest" => self.autologin_guest(),
-                "autologin_timeout" => self.autologin_timeout(),
-                "autologin_user" => self.autologin_user(),
-                "brightness" => self.brightness(),
-                "can_hibernate" => self.can_hibernate(),
-                "can_restart" => self.can_restart(),
-                "can_shutdown" => self.can_shutdown(),
-                "can_suspend" => self.can_suspend(),
-                "default_session" => self.default_session(),
-                "has_guest_account" => self.has_guest_account(),
-                "hide_users_hint" => self.hide_users_hint(),
-                "hostname" => self.hostname(),
-                "in_authentication" => self.in_authentication(),
-                "is_authenticated" => self.is_authenticated(),
-                "language" => self.language(),
-                "languages" => self.languages(),
-                "layout" => self.layout(),
-                "layouts" => self.layouts(),
-                "lock_hint" => self.lock_hint(),
-                "remote_sessions" => self.remote_sessions(),
-                "select_guest_hint" => self.select_guest_hint(),
-                "select_user_hint" => self.select_user_hint(),
-                "sessions" => self.sessions(),
-                "shared_data_directory" => self.shared_data_directory_getter(),
-                "show_manual_login_hint" => self.show_manual_login_hint(),
-                "show_remote_login_hint" => self.show_remote_login_hint(),
-                "users" => self.users(),
-                "authenticate_as_guest" => self.authenticate_as_guest(),
-                "cancel_authentication" => self.cancel_authentication(),
-                "cancel_autologin" => self.cancel_autologin(),
-                "hibernate" => self.hibernate(),
-                "restart" => self.restart(),
-                "shutdown" => self.shutdown(),
-                "suspend" => self.suspend(),
-                s => {
-                    logger_warn!("{s} does not implemented");
-                    jsc::Value::new_undefined(context)
-                }
+        match (request.kind, request.method.as_str()) {
+            (Kind::Get, "auth_state") => self.auth_state(),
+            (Kind::Get, "authentication_user") => self.authentication_user(),
+            (Kind::Get, "autologin_guest") => self.autologin_guest(),
+            (Kind::Get, "autologin_timeout") => self.autologin_timeout(),
+            (Kind::Get, "autologin_user") => self.autologin_user(),
+            (Kind::Get, "battery_data") => self.battery_data(),
+            (Kind::Get, "brightness") => self.brightness(),
+            (Kind::Get, "can_access_battery") => self.can_access_battery(),
+            (Kind::Get, "can_access_brightness") => self.can_access_brightness(),
+            (Kind::Get, "can_hibernate") => self.can_hibernate(),
+            (Kind::Get, "can_restart") => self.can_restart(),
+            (Kind::Get, "can_shutdown") => self.can_shutdown(),
+            (Kind::Get, "can_suspend") => self.can_suspend(),
+            (Kind::Get, "default_session") => self.default_session(),
+            (Kind::Get, "has_guest_account") => self.has_guest_account(),
+            (Kind::Get, "hide_users_hint") => self.hide_users_hint(),
+            (Kind::Get, "hostname") => self.hostname(),
+            (Kind::Get, "in_authentication") => self.in_authentication(),
+            (Kind::Get, "is_authenticated") => self.is_authenticated(),
+            (Kind::Get, "language") => self.language(),
+            (Kind::Get, "languages") => self.languages(),
+            (Kind::Get, "layout") => self.layout(),
+            (Kind::Get, "last_state") => self.last_state(),
+            (Kind::Get, "layouts") => self.layouts(),
+            (Kind::Get, "lock_hint") => self.lock_hint(),
+            (Kind::Get, "recent_users") => self.recent_users(),
+            (Kind::Get, "remote_sessions") => self.remote_sessions(),
+            (Kind::Get, "select_guest_hint") => self.select_guest_hint(),
+            (Kind::Get, "select_user_hint") => self.select_user_hint(),
+            (Kind::Get, "sessions") => self.sessions(),
+            (Kind::Get, "shared_data_directory") => self.shared_data_directory_getter(),
+            (Kind::Get, "show_manual_login_hint") => self.show_manual_login_hint(),
+            (Kind::Get, "show_remote_login_hint") => self.show_remote_login_hint(),
+            (Kind::Get, "users") => self.users(),
+            (Kind::Set, "brightness") | (Kind::Call, "brightness_set") => {
+                self.set_brightness(request.arg::<f64>(0).unwrap_or_default() as i32)
             }
-        } else {
-            match name {
-                "brightness" => self.set_brightness(params[0].to_int32()),
-                "layout" => self.set_layout(params[0].clone()),
-                "authenticate" => self.authenticate(Some(&params[0].to_string())),
-                "respond" => self.respond(&params[0].to_string()),
-                "set_language" => self.set_language(&params[0].to_string()),
-                "start_session" => self.start_session(Some(&params[0].to_string())),
-                s => {
-                    logger_warn!("{s} does not implemented");
-                    jsc::Value::new_undefined(context)
-                }
+            (Kind::Set, "layout") => self.set_layout(request.arg(0)),
+            (Kind::Call, "auth_failures") => {
+                self.auth_failures(&request.arg::<String>(0).unwrap_or_default())
+            }
+            (Kind::Call, "authenticate") => self.authenticate(request.arg::<String>(0).as_deref()),
+            (Kind::Call, "authenticate_as_guest") => self.authenticate_as_guest(),
+            (Kind::Call, "authenticate_remote") => self.authenticate_remote(
+                &request.arg::<String>(0).unwrap_or_default(),
+                request.arg::<String>(1).as_deref(),
+            ),
+            (Kind::Call, "brightness_decrease") => {
+                self.brightness_decrease(request.arg::<f64>(0).map(|q| q as i32))
+            }
+            (Kind::Call, "brightness_increase") => {
+                self.brightness_increase(request.arg::<f64>(0).map(|q| q as i32))
+            }
+            (Kind::Call, "cancel_authentication") => self.cancel_authentication(),
+            (Kind::Call, "cancel_autologin") => self.cancel_autologin(),
+            (Kind::Call, "fingerprint_available") => {
+                self.fingerprint_available(&request.arg::<String>(0).unwrap_or_default())
+            }
+            (Kind::Call, "hibernate") => self.hibernate(),
+            (Kind::Call, "respond") => self.respond(&request.arg::<String>(0).unwrap_or_default()),
+            (Kind::Call, "restart") => self.restart(),
+            (Kind::Call, "set_language") => {
+                self.set_language(&request.arg::<String>(0).unwrap_or_default())
+            }
+            (Kind::Call, "shutdown") => self.shutdown(),
+            (Kind::Call, "start_session") => {
+                self.start_session(request.arg::<String>(0).as_deref())
+            }
+            (Kind::Call, "suspend") => self.suspend(),
+            (kind, name) => {
+                logger_warn!("lightdm.{name} {kind} is not implemented");
+                jsc::Value::new_undefined(context)
             }
-        };
-
-        if let Some(json) = ret.to_json(0) {
-            json.to_variant()
-        } else {
-            "undefined".to_variant()
         }
     }
 
+    fn auth_state(&self) -> jsc::Value {
+        let context = &self.context;
+        ext::to_jscvalue(context, &*self.auth.borrow()).unwrap_or_else(|e| {
+            logger_error!("Could not convert lightdm.auth_state: {e}");
+            jsc::Value::new_undefined(context)
+        })
+    }
+
+    fn auth_failures(&self, username: &str) -> jsc::Value {
+        let context = &self.context;
+        let failures = self.faillock.failures(username);
+        ext::to_jscvalue(context, &failures).unwrap_or_else(|e| {
+            logger_error!("Could not convert lightdm.auth_failures: {e}");
+            jsc::Value::new_undefined(context)
+        })
+    }
+
     fn authentication_user(&self) -> jsc::Value {
         let context = &self.context;
         if let Some(user) = self.greeter.authentication_user() {
@@ -203,13 +426,53 @@ impl LightDM {
         jsc::Value::new_boolean(&self.context, value)
     }
 
+    fn battery_data(&self) -> jsc::Value {
+        let context = &self.context;
+        match self.battery.data() {
+            Some(data) => data.to_jscvalue(context),
+            None => jsc::Value::new_null(context),
+        }
+    }
+
+    fn can_access_battery(&self) -> jsc::Value {
+        jsc::Value::new_boolean(&self.context, self.battery.available())
+    }
+
+    fn can_access_brightness(&self) -> jsc::Value {
+        jsc::Value::new_boolean(&self.context, self.backlight.available())
+    }
+
     fn brightness(&self) -> jsc::Value {
-        jsc::Value::new_number(&self.context, *self.brightness.borrow() as f64)
+        let value = self.backlight.brightness().unwrap_or(-1);
+        jsc::Value::new_number(&self.context, value as f64)
     }
 
     fn set_brightness(&self, brightness: i32) -> jsc::Value {
-        self.brightness.replace(brightness);
-        jsc::Value::new_boolean(&self.context, true)
+        let context = &self.context;
+        if !self.backlight.available() {
+            return jsc::Value::new_boolean(context, false);
+        }
+
+        let browsers = self.browsers.clone();
+        self.backlight
+            .set_brightness(brightness, move || greeter::brightness_update(&browsers));
+        jsc::Value::new_boolean(context, true)
+    }
+
+    fn brightness_increase(&self, quantity: Option<i32>) -> jsc::Value {
+        let quantity = quantity
+            .filter(|quantity| *quantity > 0)
+            .unwrap_or(self.backlight_value);
+        let current = self.backlight.brightness().unwrap_or(0);
+        self.set_brightness(current + quantity)
+    }
+
+    fn brightness_decrease(&self, quantity: Option<i32>) -> jsc::Value {
+        let quantity = quantity
+            .filter(|quantity| *quantity > 0)
+            .unwrap_or(self.backlight_value);
+        let current = self.backlight.brightness().unwrap_or(0);
+        self.set_brightness(current - quantity)
     }
 
     fn default_session(&self) -> jsc::Value {
@@ -280,28 +543,20 @@ impl LightDM {
         }
     }
 
-    fn set_layout(&self, value: jsc::Value) -> jsc::Value {
+    fn set_layout(&self, value: Option<LayoutArg>) -> jsc::Value {
         let context = &self.context;
-        if !value.object_has_property("name")
-            || !value.object_has_property("description")
-            || !value.object_has_property("short_description")
-        {
-            context.throw("Invalid LightDMLayout");
-        }
-
-        let name = value.object_get_property("name").and_then(|s| {
-            if s.is_string() {
-                Some(s.to_string())
-            } else {
-                None
-            }
+        let layout = value.and_then(|value| {
+            lightdm::functions::layouts()
+                .into_iter()
+                .find(|l| l.name().is_some_and(|name| name == value.name))
         });
-
-        let layout = lightdm::functions::layouts()
-            .into_iter()
-            .find(|l| name == l.name().map(|s| s.to_string()));
-        lightdm::functions::set_layout(&layout.unwrap());
-        jsc::Value::new_boolean(context, true)
+        match layout {
+            Some(layout) => {
+                lightdm::functions::set_layout(&layout);
+                jsc::Value::new_boolean(context, true)
+            }
+            None => jsc::Value::new_boolean(context, false),
+        }
     }
 
     fn layouts(&self) -> jsc::Value {
@@ -319,12 +574,8 @@ impl LightDM {
     }
 
     fn remote_sessions(&self) -> jsc::Value {
-        let context = &self.context;
-        let sessions: Vec<jsc::Value> = lightdm::functions::remote_sessions()
-            .iter()
-            .map(|session| session.to_jscvalue(context))
-            .collect();
-        jsc::Value::new_array_from_garray(context, &sessions)
+        let sessions = lightdm::functions::remote_sessions();
+        self.sessions_to_jscvalue(&sessions, true)
     }
 
     fn select_guest_hint(&self) -> jsc::Value {
@@ -332,19 +583,43 @@ impl LightDM {
         jsc::Value::new_boolean(&self.context, value)
     }
 
+    /// The hint of LightDM, or the last user who logged in
     fn select_user_hint(&self) -> jsc::Value {
         let context = &self.context;
-        match self.greeter.select_user_hint() {
-            Some(value) => jsc::Value::new_string(context, Some(value.as_str())),
-            None => jsc::Value::new_null(context),
+        let filter = UserFilter::new(&self.users.borrow());
+        let hint = self
+            .greeter
+            .select_user_hint()
+            .map(|hint| hint.to_string())
+            .or_else(|| self.state.state().user.clone());
+        match hint {
+            Some(value) if filter.allows_name(&value) => {
+                jsc::Value::new_string(context, Some(value.as_str()))
+            }
+            _ => jsc::Value::new_null(context),
         }
     }
 
     fn sessions(&self) -> jsc::Value {
+        let sessions = sessions::arrange(lightdm::functions::sessions(), &self.sessions.borrow());
+        self.sessions_to_jscvalue(&sessions, false)
+    }
+
+    /// The sessions with the fields read from their `.desktop` file
+    fn sessions_to_jscvalue(&self, sessions: &[lightdm::Session], remote: bool) -> jsc::Value {
         let context = &self.context;
-        let sessions: Vec<jsc::Value> = lightdm::functions::sessions()
+        let sessions: Vec<jsc::Value> = sessions
             .iter()
-            .map(|session| session.to_jscvalue(context))
+            .map(|session| {
+                let info = session
+                    .key()
+                    .map(|key| {
+                        let ty = session.session_type();
+                        SessionInfo::read(&key, ty.as_deref(), remote)
+                    })
+                    .unwrap_or_default();
+                SessionValue { session, info }.to_jscvalue(context)
+            })
             .collect();
         jsc::Value::new_array_from_garray(context, &sessions)
     }
@@ -370,46 +645,151 @@ impl LightDM {
     }
 
     fn users(&self) -> jsc::Value {
+        let users = self.listed_users();
+        self.users_to_jscvalue(&users)
+    }
+
+    fn recent_users(&self) -> jsc::Value {
+        let mut users: Vec<ListedUser> = self
+            .listed_users()
+            .into_iter()
+            .filter(|listed| listed.last_login.is_some())
+            .collect();
+        users.sort_by_key(|listed| std::cmp::Reverse(listed.last_login));
+        self.users_to_jscvalue(&users)
+    }
+
+    fn listed_users(&self) -> Vec<ListedUser> {
+        let Some(userlist) = &self.user_list else {
+            return vec![];
+        };
+        let last_logins = self
+            .last_logins
+            .borrow_mut()
+            .get_or_insert_with(|| {
+                Rc::new(LastLogins::read(
+                    Path::new(last_login::WTMP),
+                    Path::new(last_login::LASTLOG),
+                ))
+            })
+            .clone();
+        users::arrange(userlist.users(), &self.users.borrow(), &last_logins)
+    }
+
+    fn users_to_jscvalue(&self, users: &[ListedUser]) -> jsc::Value {
+        let context = &self.context;
+        let state = self.state.state();
+        let users: Vec<jsc::Value> = users
+            .iter()
+            .map(|listed| {
+                let remembered = listed.user.name().and_then(|name| state.get(&name));
+                listed.value(remembered).to_jscvalue(context)
+            })
+            .collect();
+        jsc::Value::new_array_from_garray(context, &users)
+    }
+
+    fn last_state(&self) -> jsc::Value {
         let context = &self.context;
-        let users = match &self.user_list {
-            Some(userlist) => userlist
+        ext::to_jscvalue(context, &*self.state.state()).unwrap_or_else(|e| {
+            logger_error!("Could not convert lightdm.last_state: {e}");
+            jsc::Value::new_undefined(context)
+        })
+    }
+
+    /// What the user being authenticated logs in with, to be remembered once the session started
+    fn user_state(&self, session: Option<&str>) -> Option<UserState> {
+        let username = self.greeter.authentication_user()?.to_string();
+        let user = self.user_list.as_ref().and_then(|userlist| {
+            userlist
                 .users()
-                .iter()
-                .map(|user| user.to_jscvalue(context))
-                .collect::<Vec<jsc::Value>>(),
-            None => vec![],
+                .into_iter()
+                .find(|user| user.name().as_deref() == Some(username.as_str()))
+        });
+        let language = match &*self.language.borrow() {
+            Some((user, language)) if *user == username => Some(language.clone()),
+            _ => user
+                .as_ref()
+                .and_then(|user| user.language())
+                .map(|l| l.to_string()),
         };
-        jsc::Value::new_array_from_garray(context, &users)
+        let session = session
+            .map(str::to_string)
+            .or_else(|| {
+                user.as_ref()
+                    .and_then(|user| user.session())
+                    .map(|s| s.to_string())
+            })
+            .or_else(|| self.greeter.default_session_hint().map(|s| s.to_string()));
+        let layout = lightdm::functions::layout()
+            .and_then(|layout| layout.name())
+            .map(|name| name.to_string());
+        Some(UserState {
+            username,
+            session,
+            language,
+            layout,
+        })
+    }
+
+    /// Whether `username` is left out by the filters of the `users` configuration while manual
+    /// login is hidden, so that it may not log in
+    fn filtered_out(&self, username: &str) -> bool {
+        !self.greeter.shows_manual_login_hint()
+            && !UserFilter::new(&self.users.borrow()).allows_name(username)
     }
 
     fn authenticate(&self, username: Option<&str>) -> jsc::Value {
-        let context = &self.context;
-        if let Err(e) = self.greeter.authenticate(username) {
-            logger_error!("{}", e.message());
-            jsc::Value::new_boolean(context, false)
-        } else {
-            jsc::Value::new_boolean(context, true)
+        if let Some(username) = username
+            && self.filtered_out(username)
+        {
+            logger_warn!(
+                "lightdm.authenticate: {username} is not listed and manual login is hidden"
+            );
+            return jsc::Value::new_boolean(&self.context, false);
         }
+
+        self.authentication_step(
+            Transition::Authenticate {
+                with_user: username.is_some(),
+                remote_session: None,
+            },
+            |greeter| greeter.authenticate(username),
+        )
     }
 
-    fn authenticate_as_guest(&self) -> jsc::Value {
-        let context = &self.context;
-        if let Err(e) = self.greeter.authenticate_as_guest() {
-            logger_error!("{}", e.message());
-            jsc::Value::new_boolean(context, false)
-        } else {
-            jsc::Value::new_boolean(context, true)
+    fn authenticate_remote(&self, session: &str, username: Option<&str>) -> jsc::Value {
+        let known = lightdm::functions::remote_sessions()
+            .iter()
+            .any(|remote| remote.key().as_deref() == Some(session));
+        if !known {
+            logger_warn!("lightdm.authenticate_remote: {session} is not a remote session");
+            return jsc::Value::new_boolean(&self.context, false);
         }
+
+        self.authentication_step(
+            Transition::Authenticate {
+                with_user: username.is_some(),
+                remote_session: Some(session.to_string()),
+            },
+            |greeter| greeter.authenticate_remote(session, username),
+        )
+    }
+
+    fn authenticate_as_guest(&self) -> jsc::Value {
+        self.authentication_step(
+            Transition::Authenticate {
+                with_user: true,
+                remote_session: None,
+            },
+            |greeter| greeter.authenticate_as_guest(),
+        )
     }
 
     fn cancel_authentication(&self) -> jsc::Value {
-        let context = &self.context;
-        if let Err(e) = self.greeter.cancel_authentication() {
-            logger_error!("{}", e.message());
-            jsc::Value::new_boolean(context, false)
-        } else {
-            jsc::Value::new_boolean(context, true)
-        }
+        self.authentication_step(Transition::Cancel, |greeter| {
+            greeter.cancel_authentication()
+        })
     }
 
     fn cancel_autologin(&self) -> jsc::Value {
@@ -417,6 +797,11 @@ impl LightDM {
         jsc::Value::new_boolean(&self.context, true)
     }
 
+    fn fingerprint_available(&self, username: &str) -> jsc::Value {
+        let value = self.fingerprint.available(username);
+        jsc::Value::new_boolean(&self.context, value)
+    }
+
     fn hibernate(&self) -> jsc::Value {
         let context = &self.context;
         if let Err(e) = lightdm::functions::hibernate() {
@@ -428,13 +813,7 @@ impl LightDM {
     }
 
     fn respond(&self, response: &str) -> jsc::Value {
-        let context = &self.context;
-        if let Err(e) = self.greeter.respond(response) {
-            logger_error!("{}", e.message());
-            jsc::Value::new_boolean(context, false)
-        } else {
-            jsc::Value::new_boolean(context, true)
-        }
+        self.authentication_step(Transition::Respond, |greeter| greeter.respond(response))
     }
 
     fn restart(&self) -> jsc::Value {
@@ -453,6 +832,9 @@ impl LightDM {
             logger_error!("{}", e.message());
             jsc::Value::new_boolean(context, false)
         } else {
+            let user = self.greeter.authentication_user();
+            self.language
+                .replace(user.map(|user| (user.to_string(), language.to_string())));
             jsc::Value::new_boolean(context, true)
         }
     }
@@ -477,10 +859,98 @@ impl LightDM {
         }
     }
 
+    /// Fade the windows out and start the session without blocking, the outcome is reported by
+    /// the `session_started` and `session_failed` events
     fn start_session(&self, session: Option<&str>) -> jsc::Value {
+        // The username PAM prompted for, if `authenticate` was called without one
+        if self.auth.borrow().remote_session.is_none()
+            && let Some(username) = self.greeter.authentication_user()
+            && self.filtered_out(&username)
+        {
+            logger_warn!(
+                "lightdm.start_session: {username} is not listed and manual login is hidden"
+            );
+            return jsc::Value::new_boolean(&self.context, false);
+        }
+
+        // A remote authentication is for that remote session only, which is not remembered
+        let (session, remembered) = match &self.auth.borrow().remote_session {
+            Some(remote) => (Some(remote.clone()), None),
+            None => (session.map(str::to_string), self.user_state(session)),
+        };
+        let known_users: Vec<String> = self
+            .user_list
+            .as_ref()
+            .map(|userlist| {
+                userlist
+                    .users()
+                    .iter()
+                    .filter_map(|user| user.name().map(|name| name.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let browsers = self.browsers.clone();
+        let auth = self.auth.clone();
+        let state = self.state.clone();
+        let fade_out = self.session_fade_out;
+        self.authentication_step(Transition::StartSession, move |greeter| {
+            greeter::session_starting(&browsers, session.as_deref());
+            let greeter = greeter.clone();
+            let windows = browsers.clone();
+            browser::fade(&windows, 0.0, fade_out, move || {
+                greeter.start_session(session.as_deref(), Cancellable::NONE, move |result| {
+                    match result {
+                        Ok(()) => {
+                            if let Some(remembered) = remembered {
+                                state.record(remembered, &known_users);
+                            }
+                            greeter::session_started(&browsers)
+                        }
+                        Err(e) => {
+                            logger_error!("{}", e.message());
+                            browser::fade(&browsers, 1.0, fade_out, || {});
+                            report(&browsers, &auth, Transition::SessionFailed);
+                            greeter::session_failed(&browsers, e.message());
+                        }
+                    }
+                });
+            });
+            Ok(())
+        })
+    }
+
+    /// Advance the authentication state machine with a transition requested by the theme, then
+    /// let `action` perform it. The previous state is restored if `action` fails. Authenticating
+    /// and starting a session wait for a required login banner to be acknowledged.
+    fn authentication_step(
+        &self,
+        transition: Transition,
+        action: impl FnOnce(&lightdm::Greeter) -> Result<(), glib::Error>,
+    ) -> jsc::Value {
         let context = &self.context;
-        if let Err(e) = self.greeter.start_session_sync(session) {
+        let starts = matches!(
+            transition,
+            Transition::Authenticate { .. } | Transition::StartSession
+        );
+        if starts && self.banner.pending() {
+            logger_warn!(
+                "lightdm.{transition} is not allowed before the login banner is acknowledged"
+            );
+            return jsc::Value::new_boolean(context, false);
+        }
+
+        let previous = self.auth.borrow().clone();
+        if let Err(e) = advance(&self.browsers, &self.auth, transition) {
+            logger_warn!("{e}");
+            return jsc::Value::new_boolean(context, false);
+        }
+
+        if let Err(e) = action(&self.greeter) {
             logger_error!("{}", e.message());
+            if *self.auth.borrow() != previous {
+                self.auth.replace(previous.clone());
+                greeter::auth_state_changed(&self.browsers, &previous);
+            }
             jsc::Value::new_boolean(context, false)
         } else {
             jsc::Value::new_boolean(context, true)
@@ -488,73 +958,118 @@ impl LightDM {
     }
 }
 
+fn advance(
+    browsers: &[Browser],
+    auth: &RefCell<AuthStatus>,
+    transition: Transition,
+) -> Result<(), String> {
+    let next = auth.borrow().next(transition)?;
+    if let Some(status) = next {
+        let previous = auth.replace(status.clone());
+        greeter::auth_state_changed(browsers, &status);
+        if status.password_change && !previous.password_change {
+            greeter::password_change_required(browsers);
+        }
+    }
+    Ok(())
+}
+
+/// Advance the authentication state machine with a transition reported by LightDM
+fn report(browsers: &[Browser], auth: &RefCell<AuthStatus>, transition: Transition) {
+    if let Err(e) = advance(browsers, auth, transition) {
+        logger_warn!("{e}");
+    }
+}
+
 mod greeter {
-    use gtk::{
-        gio::Cancellable,
-        glib::{translate::IntoGlib, variant::ToVariant},
-    };
-    use webkit::{UserMessage, prelude::WebViewExt};
+    use gtk::glib::translate::IntoGlib;
+    use serde_json::{Value, json};
+
+    use super::{AuthStatus, BatteryData, Browser, PromptPurpose};
+    use crate::bridge::emit;
 
-    use super::Browser;
+    pub(super) fn auth_state_changed(browsers: &[Browser], status: &AuthStatus) {
+        let status = serde_json::to_value(status).unwrap_or(Value::Null);
+        emit(browsers, "lightdm", "auth_state_changed", vec![status]);
+    }
 
     pub(super) fn authentication_complete(browsers: &[Browser]) {
-        browsers.iter().map(|b| b.webview()).for_each(|webview| {
-            let parameters = ["authentication_complete", "[]"].to_variant();
-            let message = UserMessage::new("lightdm", Some(&parameters));
-            webview.send_message_to_page(&message, Cancellable::NONE, |_| {});
-        });
+        emit(browsers, "lightdm", "authentication_complete", vec![]);
+    }
+
+    pub(super) fn reset(browsers: &[Browser]) {
+        emit(browsers, "lightdm", "reset", vec![]);
+    }
+
+    pub(super) fn brightness_update(browsers: &[Browser]) {
+        emit(browsers, "lightdm", "brightness_update", vec![]);
+    }
+
+    pub(super) fn fingerprint_status(browsers: &[Browser], result: &str, done: bool) {
+        let args = vec![json!(result), json!(done)];
+        emit(browsers, "lightdm", "fingerprint_status", args);
+    }
+
+    pub(super) fn fingerprint_available_changed(
+        browsers: &[Browser],
+        username: &str,
+        available: bool,
+    ) {
+        let args = vec![json!(username), json!(available)];
+        emit(browsers, "lightdm", "fingerprint_available_changed", args);
+    }
+
+    pub(super) fn battery_update(browsers: &[Browser], data: Option<&BatteryData>) {
+        let data = serde_json::to_value(data).unwrap_or(Value::Null);
+        emit(browsers, "lightdm", "battery_update", vec![data]);
     }
 
     pub(super) fn autologin_timer_expired(browsers: &[Browser]) {
-        browsers.iter().map(|b| b.webview()).for_each(|webview| {
-            let parameters = ["autologin_timer_expired", "[]"].to_variant();
-            let message = UserMessage::new("lightdm", Some(&parameters));
-            webview.send_message_to_page(&message, Cancellable::NONE, |_| {});
-        });
+        emit(browsers, "lightdm", "autologin_timer_expired", vec![]);
+    }
+
+    pub(super) fn password_change_required(browsers: &[Browser]) {
+        emit(browsers, "lightdm", "password_change_required", vec![]);
     }
 
     pub(super) fn show_prompt(
         browsers: &[Browser],
-        context: &jsc::Context,
         text: &str,
         ty: lightdm::PromptType,
+        purpose: Option<PromptPurpose>,
     ) {
-        browsers.iter().map(|b| b.webview()).for_each(|webview| {
-            let param = jsc::Value::new_array_from_garray(
-                context,
-                &[
-                    jsc::Value::new_string(context, Some(text)),
-                    jsc::Value::new_number(context, ty.into_glib() as f64),
-                ],
-            )
-            .to_json(0)
-            .expect("param parse to json failed");
-            let parameters = ["show_prompt", &param].to_variant();
-            let message = UserMessage::new("lightdm", Some(&parameters));
-            webview.send_message_to_page(&message, Cancellable::NONE, |_| {});
-        });
+        let args = vec![json!(text), json!(ty.into_glib()), json!(purpose)];
+        emit(browsers, "lightdm", "show_prompt", args);
     }
 
-    pub(super) fn show_message(
-        browsers: &[Browser],
-        context: &jsc::Context,
-        text: &str,
-        ty: lightdm::MessageType,
-    ) {
-        browsers.iter().map(|b| b.webview()).for_each(|webview| {
-            let param = jsc::Value::new_array_from_garray(
-                context,
-                &[
-                    jsc::Value::new_string(context, Some(text)),
-                    jsc::Value::new_number(context, ty.into_glib() as f64),
-                ],
-            )
-            .to_json(0)
-            .expect("param parse to json failed");
-
-            let parameters = ["show_message", &param].to_variant();
-            let message = UserMessage::new("lightdm", Some(&parameters));
-            webview.send_message_to_page(&message, Cancellable::NONE, |_| {});
-        });
+    pub(super) fn session_starting(browsers: &[Browser], session: Option<&str>) {
+        emit(
+            browsers,
+            "lightdm",
+            "session_starting",
+            vec![json!(session)],
+        );
+    }
+
+    pub(super) fn session_started(browsers: &[Browser]) {
+        emit(browsers, "lightdm", "session_started", vec![]);
+    }
+
+    pub(super) fn session_failed(browsers: &[Browser], error: &str) {
+        emit(browsers, "lightdm", "session_failed", vec![json!(error)]);
+    }
+
+    pub(super) fn show_message(browsers: &[Browser], text: &str, ty: lightdm::MessageType) {
+        let args = vec![json!(text), json!(ty.into_glib())];
+        emit(browsers, "lightdm", "show_message", args);
+    }
+
+    pub(super) fn user_locked(browsers: &[Browser], username: &str, unlock_time: Option<u64>) {
+        let args = vec![json!(username), json!(unlock_time)];
+        emit(browsers, "lightdm", "user_locked", args);
+    }
+
+    pub(super) fn users_changed(browsers: &[Browser]) {
+        emit(browsers, "lightdm", "users_changed", vec![]);
     }
 }
@@ -2,26 +2,50 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+mod auth;
+mod backlight;
+mod banner;
+mod battery;
+mod faillock;
+mod fingerprint;
 mod greeter_comm;
 mod greeter_config;
+mod last_login;
+mod last_state;
 mod lightdm;
+mod pam;
+mod sessions;
 mod theme_utils;
+mod users;
+
+use gtk::{gio::Cancellable, glib::variant::ToVariant};
+use webkit::{UserMessage, prelude::WebViewExt};
+
+use crate::browser::Browser;
 
 pub use dispatcher::Dispatcher;
 
 mod dispatcher {
-    use gtk::glib::VariantTy;
+    use gtk::{
+        gio::FileMonitor,
+        glib::{clone, variant::ToVariant},
+    };
+    use protocol::{Reply, Request};
     use webkit::UserMessage;
 
-    use std::{cell::RefCell, rc::Rc};
+    use std::{cell::RefCell, path::Path, rc::Rc};
 
     use crate::{
         browser::{Browser, BrowserProperties},
         settings::Settings,
+        watcher,
     };
 
     use super::{
-        greeter_comm::GreeterComm, greeter_config::GreeterConfig, lightdm::LightDM,
+        banner::BannerState,
+        greeter_comm::GreeterComm,
+        greeter_config::{self, GreeterConfig},
+        lightdm::LightDM,
         theme_utils::ThemeUtils,
     };
 
@@ -30,26 +54,103 @@ mod dispatcher {
         greeter_comm: GreeterComm,
         lightdm: LightDM,
         theme_utils: ThemeUtils,
+        banner: Rc<BannerState>,
+        browsers: Rc<Vec<Browser>>,
+        monitors: RefCell<Vec<FileMonitor>>,
     }
 
     impl Dispatcher {
         pub fn new(config: Settings, context: jsc::Context, browsers: Rc<Vec<Browser>>) -> Self {
             let theme = config.theme().to_string();
-            let lightdm = LightDM::new(context.clone(), browsers.clone());
+            let banner = Rc::new(BannerState::new(config.banner_acknowledgement_required()));
+            let lightdm = LightDM::new(context.clone(), browsers.clone(), &config, banner.clone());
             let allowed_dirs = [
                 config.themes_dir().unwrap().to_string(),
                 config.branding_background_images_dir().to_string(),
                 lightdm.shared_data_directory().to_string(),
             ];
             let theme_utils = ThemeUtils::new(context.clone(), &allowed_dirs, &theme);
-            let greeter_config = RefCell::new(GreeterConfig::new(context.clone(), config));
-            let greeter_comm = GreeterComm::new(context, browsers);
+            let greeter_config =
+                RefCell::new(GreeterConfig::new(context.clone(), config, banner.clone()));
+            let greeter_comm = GreeterComm::new(context, browsers.clone());
             Self {
                 greeter_config,
                 greeter_comm,
                 lightdm,
                 theme_utils,
+                banner,
+                browsers,
+                monitors: Default::default(),
+            }
+        }
+
+        /// Watch the config files and the active theme, in debug mode only
+        pub fn watch(self: &Rc<Self>) {
+            let config = self.greeter_config.borrow();
+            if !config.debug_mode() {
+                self.monitors.borrow_mut().clear();
+                return;
+            }
+
+            let mut monitors = watcher::watch(
+                &config.config_files(),
+                clone!(
+                    #[weak(rename_to = dispatcher)]
+                    self,
+                    move |_| dispatcher.reload_configuration()
+                ),
+            );
+            if let Some(theme_dir) = config.theme_dir() {
+                monitors.extend(watcher::watch(
+                    &watcher::directory_tree(&theme_dir),
+                    clone!(
+                        #[weak(rename_to = dispatcher)]
+                        self,
+                        move |path| dispatcher.reload_theme(path)
+                    ),
+                ));
             }
+            self.monitors.replace(monitors);
+        }
+
+        fn reload_configuration(self: &Rc<Self>) {
+            logger_debug!("Configuration changed, reloading");
+            let settings = self.greeter_config.borrow().reload();
+            let theme_changed = {
+                let config = self.greeter_config.borrow();
+                config.primary_html() != settings.primary_html()
+                    || config.secondary_html() != settings.secondary_html()
+            };
+            self.banner
+                .set_required(settings.banner_acknowledgement_required());
+            self.lightdm.reload_configuration(&settings);
+            **self.greeter_config.borrow_mut() = settings;
+
+            if theme_changed {
+                self.change_theme(None);
+            } else {
+                greeter_config::config_changed(&self.browsers);
+            }
+            self.watch();
+        }
+
+        fn reload_theme(self: &Rc<Self>, path: &Path) {
+            logger_debug!("{} changed, reloading theme", path.display());
+            if path.is_dir() {
+                // Watch the new directory too
+                self.watch();
+            }
+            let config = self.greeter_config.borrow();
+            let primary = path == Path::new(&config.primary_html());
+            let secondary = config
+                .secondary_html()
+                .is_some_and(|secondary| path == Path::new(&secondary));
+            self.greeter_comm
+                .reload_theme(|browser| match (primary, secondary) {
+                    (true, false) => browser.primary(),
+                    (false, true) => !browser.primary(),
+                    _ => true,
+                });
         }
 
         pub fn change_theme(&self, theme: Option<&str>) {
@@ -62,78 +163,75 @@ mod dispatcher {
             self.greeter_comm.load_theme(primary_html, secondary_html);
         }
 
+        /// The theme API version required by the active theme, if this greeter does not provide it
+        pub fn unsupported_api_version(&self) -> Option<String> {
+            self.greeter_config
+                .borrow()
+                .unsupported_api_version()
+                .map(str::to_string)
+        }
+
         pub fn send(&self, message: &UserMessage, win_props: &BrowserProperties) {
             let reply = match parse(message) {
-                Message::GreeterConfig((method, _)) => {
-                    // logger_warn!("greeter_config.{method}({json_params})");
-                    let reply = self.greeter_config.borrow().handle(&method);
-                    UserMessage::new("reply", Some(&reply))
-                }
-                Message::GreeterComm((method, json_params)) => {
-                    // logger_warn!("greeter_comm.{method}({json_params})");
-                    let reply = self.greeter_comm.handle(&method, &json_params, win_props);
-                    UserMessage::new("reply", Some(&reply))
-                }
-                Message::LigthDM((method, json_params)) => {
-                    // logger_warn!("lightdm.{method}({json_params})");
-                    let reply = self.lightdm.handle(&method, &json_params);
-                    UserMessage::new("reply", Some(&reply))
-                }
-                Message::ThemeUtils((method, json_params)) => {
-                    // logger_warn!("theme_utils.{method}({json_params})");
-                    let reply = self.theme_utils.handle(&method, &json_params);
-                    UserMessage::new("reply", Some(&reply))
+                Ok(request) => {
+                    let value = match request.target.as_str() {
+                        "greeter_config" => self.greeter_config.borrow().handle(&request),
+                        "greeter_comm" => self.greeter_comm.handle(&request, win_props),
+                        "lightdm" => self.lightdm.handle(&request),
+                        "theme_utils" => self.theme_utils.handle(&request),
+                        target => unreachable!("{target} passed Request::resolve"),
+                    };
+                    // `undefined` has no JSON representation
+                    let json = value.to_json(0).unwrap_or("null".into());
+                    Reply::Ok(serde_json::from_str(&json).unwrap_or_default())
                 }
-                _ => {
-                    logger_warn!("{:?}", message);
-                    UserMessage::new("", None)
+                Err(e) => {
+                    logger_warn!("{e}");
+                    Reply::Error(e.to_string())
                 }
             };
-            // logger_warn!("{:?}", reply.parameters());
+            let reply = UserMessage::new("reply", Some(&reply.to_json().to_variant()));
             message.send_reply(&reply);
         }
     }
 
-    enum Message {
-        GreeterConfig((String, String)),
-        LigthDM((String, String)),
-        GreeterComm((String, String)),
-        ThemeUtils((String, String)),
-        Unknown,
-    }
-
-    fn parse(message: &UserMessage) -> Message {
-        let msg_param = message.parameters();
-        if msg_param.is_none() {
-            return Message::Unknown;
+    /// Decode the request carried by `message` and check it against the protocol definition
+    fn parse(message: &UserMessage) -> Result<Request, protocol::Error> {
+        let json = message
+            .parameters()
+            .and_then(|parameters| parameters.str().map(|s| s.to_string()))
+            .ok_or_else(|| protocol::Error::Malformed(format!("{message:?}")))?;
+        let request = Request::from_json(&json)?;
+        if message.name().as_deref() != Some(request.target.as_str()) {
+            return Err(protocol::Error::Malformed(format!(
+                "{} sent to {:?}",
+                request.target,
+                message.name()
+            )));
         }
+        request.resolve()?;
+        Ok(request)
+    }
+}
 
-        let msg_param = msg_param.unwrap();
-        if msg_param.is_type(VariantTy::ARRAY) {
-            let p_len = msg_param.n_children();
-            if p_len == 0 || p_len > 2 {
-                return Message::Unknown;
-            }
-        } else {
-            return Message::Unknown;
-        }
-
-        let method_var = msg_param.child_value(0);
-        let params_var = msg_param.child_value(1);
-
-        let method = method_var.str().unwrap().to_string();
-        let json_params = params_var.str().unwrap().to_string();
-
-        if method.is_empty() {
-            return Message::Unknown;
+/// Emit the protocol event `target.name` with `args` to every browser
+fn emit(browsers: &[Browser], target: &str, name: &str, args: Vec<serde_json::Value>) {
+    let event = protocol::object(target).and_then(|object| object.event(name));
+    match event.map(|event| event.check(&args)) {
+        Some(Ok(())) => {}
+        Some(Err(e)) => {
+            logger_error!("{e}");
+            return;
         }
-
-        match message.name().as_deref() {
-            Some("lightdm") => Message::LigthDM((method, json_params)),
-            Some("greeter_config") => Message::GreeterConfig((method, json_params)),
-            Some("greeter_comm") => Message::GreeterComm((method, json_params)),
-            Some("theme_utils") => Message::ThemeUtils((method, json_params)),
-            _ => Message::Unknown,
+        None => {
+            logger_error!("{target}.{name} is not a known event");
+            return;
         }
     }
+
+    let parameters = (target, name, serde_json::Value::Array(args).to_string()).to_variant();
+    browsers.iter().map(|b| b.webview()).for_each(|webview| {
+        let message = UserMessage::new(protocol::EVENT_MESSAGE, Some(&parameters));
+        webview.send_message_to_page(&message, Cancellable::NONE, |_| {});
+    });
 }
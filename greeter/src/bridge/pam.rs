@@ -0,0 +1,53 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Recognition of the PAM conversation changing an expired password, from the messages and
+//! prompts of pam_unix and similar modules. Only their untranslated messages are recognized.
+
+use serde::Serialize;
+
+/// What a prompt of the password-change conversation asks for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum PromptPurpose {
+    Current,
+    New,
+    Confirm,
+}
+
+const CHANGE_REQUIRED: [&str; 5] = [
+    "required to change your password",
+    "must change your password",
+    "password has expired",
+    "password expired",
+    "password aged",
+];
+
+/// Whether a message of PAM announces that the password must be changed
+pub(super) fn requires_password_change(message: &str) -> bool {
+    let message = message.to_lowercase();
+    CHANGE_REQUIRED
+        .iter()
+        .any(|pattern| message.contains(pattern))
+}
+
+/// The purpose of a prompt, `changing` if the password change was already announced. Prompts
+/// for a new password are recognized on their own.
+pub(super) fn prompt_purpose(prompt: &str, changing: bool) -> Option<PromptPurpose> {
+    let prompt = prompt.to_lowercase();
+    if !prompt.contains("password") {
+        return None;
+    }
+
+    let is_new = prompt.contains("new");
+    let confirm = ["retype", "re-enter", "repeat", "again", "confirm"]
+        .iter()
+        .any(|word| prompt.contains(word));
+    match (is_new, confirm) {
+        (_, true) => Some(PromptPurpose::Confirm),
+        (true, false) => Some(PromptPurpose::New),
+        (false, false) if changing => Some(PromptPurpose::Current),
+        (false, false) => None,
+    }
+}
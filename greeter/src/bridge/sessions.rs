@@ -0,0 +1,140 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Sessions as listed by `lightdm.sessions` and `lightdm.remote_sessions`: what their `.desktop`
+//! file adds to the sessions of LightDM, and the order and hiding of the `sessions` configuration.
+
+use gtk::{gdk, glib, prelude::*};
+use lightdm::prelude::*;
+
+use ext::prelude::*;
+
+use std::path::{Path, PathBuf};
+
+use crate::settings::Sessions;
+
+// The default `sessions-directory` and `remote-sessions-directory` of LightDM
+const LIGHTDM_SESSIONS_DIR: &str = "/usr/share/lightdm/sessions";
+const X_SESSIONS_DIR: &str = "/usr/share/xsessions";
+const WAYLAND_SESSIONS_DIR: &str = "/usr/share/wayland-sessions";
+const REMOTE_SESSIONS_DIR: &str = "/usr/share/lightdm/remote-sessions";
+const DESKTOP_ENTRY: &str = "Desktop Entry";
+const ICON_SIZE: i32 = 64;
+
+/// A `LightDMSession` with the fields of its `.desktop` file
+#[derive(ToJSCValue)]
+pub(super) struct SessionValue<'a> {
+    #[jsc(flatten)]
+    pub(super) session: &'a lightdm::Session,
+    #[jsc(flatten)]
+    pub(super) info: SessionInfo,
+}
+
+/// What the `.desktop` file of a session adds to a `LightDMSession`
+#[derive(Default, ToJSCValue)]
+pub(super) struct SessionInfo {
+    /// Path of the icon
+    icon: Option<String>,
+    desktop_names: Vec<String>,
+    /// The `TryExec` program is installed, or there is none
+    try_exec_available: bool,
+    /// The program of `Exec` is installed
+    exec_available: bool,
+}
+
+impl SessionInfo {
+    /// Read the `.desktop` file of the session `key` of type `ty`, LightDM lists X11 and Wayland
+    /// sessions with the same key from different directories
+    pub(super) fn read(key: &str, ty: Option<&str>, remote: bool) -> Self {
+        let dirs: &[&str] = match (remote, ty) {
+            (true, _) => &[REMOTE_SESSIONS_DIR],
+            (false, Some("wayland")) => &[LIGHTDM_SESSIONS_DIR, WAYLAND_SESSIONS_DIR],
+            (false, _) => &[LIGHTDM_SESSIONS_DIR, X_SESSIONS_DIR],
+        };
+        let entry = dirs
+            .iter()
+            .map(|dir| Path::new(dir).join(format!("{key}.desktop")))
+            .find(|path| path.is_file())
+            .and_then(|path| load(&path));
+        let Some(entry) = entry else {
+            logger_debug!("No desktop file found for the session {key}");
+            return Self::default();
+        };
+
+        let string = |key| entry.string(DESKTOP_ENTRY, key).ok();
+        let exec = string("Exec").and_then(|exec| {
+            glib::shell_parse_argv(exec.as_str())
+                .ok()
+                .and_then(|argv| argv.into_iter().next())
+        });
+        Self {
+            icon: string("Icon").and_then(|icon| resolve_icon(&icon)),
+            desktop_names: entry
+                .string_list(DESKTOP_ENTRY, "DesktopNames")
+                .map(|names| names.iter().map(|name| name.to_string()).collect())
+                .unwrap_or_default(),
+            try_exec_available: string("TryExec")
+                .is_none_or(|program| glib::find_program_in_path(program.as_str()).is_some()),
+            exec_available: exec
+                .is_some_and(|program| glib::find_program_in_path(program).is_some()),
+        }
+    }
+}
+
+fn load(path: &Path) -> Option<glib::KeyFile> {
+    let entry = glib::KeyFile::new();
+    match entry.load_from_file(path, glib::KeyFileFlags::NONE) {
+        Ok(()) => Some(entry),
+        Err(e) => {
+            logger_warn!("Could not read {}: {}", path.display(), e.message());
+            None
+        }
+    }
+}
+
+/// The path of an `Icon`, either a path or the name of an icon of the icon theme
+fn resolve_icon(icon: &str) -> Option<String> {
+    if Path::new(icon).is_absolute() {
+        return Path::new(icon).is_file().then(|| icon.to_string());
+    }
+
+    let theme = gtk::IconTheme::for_display(&gdk::Display::default()?);
+    if !theme.has_icon(icon) {
+        return None;
+    }
+    let paintable = theme.lookup_icon(
+        icon,
+        &[],
+        ICON_SIZE,
+        1,
+        gtk::TextDirection::None,
+        gtk::IconLookupFlags::empty(),
+    );
+    let path: PathBuf = paintable.file()?.path()?;
+    path.to_str().map(str::to_string)
+}
+
+/// The sessions which are not hidden by the `sessions` configuration, in its order
+pub(super) fn arrange(sessions: Vec<lightdm::Session>, config: &Sessions) -> Vec<lightdm::Session> {
+    let listed = |list: &[String], value: Option<glib::GString>| {
+        value.is_some_and(|value| list.iter().any(|item| *item == value.as_str()))
+    };
+    let mut sessions: Vec<lightdm::Session> = sessions
+        .into_iter()
+        .filter(|session| {
+            !listed(&config.hidden, session.key())
+                && !listed(&config.hidden_types, session.session_type())
+        })
+        .collect();
+    // The sort is stable, the sessions which are not ordered keep the order of LightDM
+    sessions.sort_by_key(|session| {
+        let key = session.key();
+        config
+            .order
+            .iter()
+            .position(|ordered| key.as_deref() == Some(ordered.as_str()))
+            .unwrap_or(usize::MAX)
+    });
+    sessions
+}
@@ -2,9 +2,8 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use gtk::glib::{self, tmp_dir, variant::ToVariant};
-
-use ext::prelude::*;
+use gtk::glib::{self, tmp_dir};
+use protocol::{Kind, Request};
 
 pub(super) struct ThemeUtils {
     context: jsc::Context,
@@ -25,43 +24,31 @@ impl ThemeUtils {
         }
     }
 
-    pub(super) fn handle(&self, name: &str, json_params: &str) -> glib::Variant {
-        let context = &self.context;
-        let params = jsc::Value::from_json(context, json_params).to_vec();
-        let ret = if "dirlist" == name && !params.is_empty() {
-            self.dirlist(&params)
-        } else {
-            jsc::Value::new_undefined(context)
-        };
-
-        if let Some(json) = ret.to_json(0) {
-            json.to_variant()
-        } else {
-            "undefined".to_variant()
+    pub(super) fn handle(&self, request: &Request) -> jsc::Value {
+        match (request.kind, request.method.as_str()) {
+            (Kind::Call, "dirlist") => self.dirlist(
+                &request.arg::<String>(0).unwrap_or_default(),
+                request.arg(1).unwrap_or_default(),
+            ),
+            (kind, name) => {
+                logger_warn!("theme_utils.{name} {kind} is not implemented");
+                jsc::Value::new_undefined(&self.context)
+            }
         }
     }
 
-    fn dirlist(&self, params: &[jsc::Value]) -> jsc::Value {
+    fn dirlist(&self, path: &str, only_images: bool) -> jsc::Value {
         let context = &self.context;
-        let null = jsc::Value::new_null(context);
-        if params.len() < 2 {
-            return null;
-        }
-
-        let jsc_path = &params[0];
-        let jsc_only_images = &params[1];
-
         let value = jsc::Value::new_array_from_garray(context, &[]);
-        if !jsc_path.is_string() || jsc_path.to_string().trim().is_empty() {
+        if path.trim().is_empty() {
             return value;
         }
-        let path = jsc_path.to_string();
 
         if path == "/" || path.starts_with("./") {
             return value;
         }
 
-        let resolved = if let Ok(p) = std::fs::canonicalize(&path) {
+        let resolved = if let Ok(p) = std::fs::canonicalize(path) {
             p
         } else {
             return value;
@@ -103,7 +90,7 @@ impl ThemeUtils {
                 .join(&resolved)
                 .join(&filename);
             let file_element = jsc::Value::new_string(context, filepath.to_str());
-            if jsc_only_images.is_boolean() && jsc_only_images.to_boolean() {
+            if only_images {
                 let s = glib::GStr::from_str_with_nul(filename.to_str().unwrap())
                     .expect("osstring to gstr error");
                 if let Ok(ft) = entry.file_type()
@@ -0,0 +1,376 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use gtk::glib::GString;
+use lightdm::prelude::*;
+
+use ext::prelude::*;
+
+use std::{cmp::Reverse, collections::HashMap};
+
+use super::{last_login::LastLogins, last_state::UserState};
+use crate::settings::{UserOrder, Users};
+
+const PASSWD: &str = "/etc/passwd";
+const GROUP: &str = "/etc/group";
+
+/// The fields of a passwd entry used by the filters
+struct Account {
+    uid: u32,
+    gid: u32,
+    shell: String,
+}
+
+struct Group {
+    gid: u32,
+    members: Vec<String>,
+}
+
+/// The filters of the `users` configuration. Accounts are read from the local databases, users
+/// from other sources only match by name and UID.
+pub(super) struct UserFilter<'a> {
+    config: &'a Users,
+    accounts: HashMap<String, Account>,
+    groups: HashMap<String, Group>,
+}
+
+impl<'a> UserFilter<'a> {
+    pub(super) fn new(config: &'a Users) -> Self {
+        let group = if config.groups.is_empty() {
+            String::new()
+        } else {
+            read_database(GROUP)
+        };
+        Self::with_databases(config, &read_database(PASSWD), &group)
+    }
+
+    /// The filters with the accounts and groups of the databases `passwd` and `group`
+    fn with_databases(config: &'a Users, passwd: &str, group: &str) -> Self {
+        Self {
+            config,
+            accounts: parse_passwd(passwd),
+            groups: parse_group(group),
+        }
+    }
+
+    pub(super) fn allows(&self, user: &lightdm::User) -> bool {
+        user.name()
+            .is_some_and(|name| self.allows_account(&name, Some(user.uid())))
+    }
+
+    /// Whether the user `username` is listed, for users which may not be in the user list
+    pub(super) fn allows_name(&self, username: &str) -> bool {
+        self.allows_account(username, None)
+    }
+
+    fn allows_account(&self, username: &str, uid: Option<u32>) -> bool {
+        let config = self.config;
+        let account = self.accounts.get(username);
+        let uid = uid.or(account.map(|account| account.uid));
+
+        if !config.include.is_empty() && !config.include.iter().any(|name| name == username) {
+            return false;
+        }
+        if config.exclude.iter().any(|name| name == username) {
+            return false;
+        }
+        if !config.uid_ranges.is_empty()
+            && !uid.is_some_and(|uid| config.uid_ranges.iter().any(|range| range.contains(uid)))
+        {
+            return false;
+        }
+        if account.is_some_and(|account| config.hidden_shells.contains(&account.shell)) {
+            return false;
+        }
+        if !config.groups.is_empty() {
+            let member = config.groups.iter().any(|name| {
+                self.groups.get(name).is_some_and(|group| {
+                    group.members.iter().any(|member| member == username)
+                        || account.is_some_and(|account| account.gid == group.gid)
+                })
+            });
+            if !member {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// A user of `lightdm.users`, with its last login in seconds since the epoch
+pub(super) struct ListedUser {
+    pub(super) user: lightdm::User,
+    pub(super) last_login: Option<u64>,
+}
+
+impl ListedUser {
+    /// The value of the user, with what it last logged in with when AccountsService does not
+    /// know it
+    pub(super) fn value(&self, remembered: Option<&UserState>) -> UserValue<'_> {
+        let user = &self.user;
+        let or_remembered = |value: Option<GString>, remembered: Option<&Option<String>>| {
+            value
+                .map(|value| value.to_string())
+                .or_else(|| remembered.cloned().flatten())
+        };
+        UserValue {
+            user,
+            last_login: self.last_login,
+            session: or_remembered(user.session(), remembered.map(|r| &r.session)),
+            language: or_remembered(user.language(), remembered.map(|r| &r.language)),
+            layout: or_remembered(user.layout(), remembered.map(|r| &r.layout)),
+        }
+    }
+}
+
+/// A `LightDMUser` with its last login in seconds since the epoch, and the session, language and
+/// layout it last logged in with
+#[derive(ToJSCValue)]
+pub(super) struct UserValue<'a> {
+    #[jsc(flatten)]
+    user: &'a lightdm::User,
+    last_login: Option<u64>,
+    session: Option<String>,
+    language: Option<String>,
+    layout: Option<String>,
+}
+
+/// The users allowed by the filters of the `users` configuration, in its order with the pinned
+/// users first
+pub(super) fn arrange(
+    users: Vec<lightdm::User>,
+    config: &Users,
+    last_logins: &LastLogins,
+) -> Vec<ListedUser> {
+    let filter = UserFilter::new(config);
+    let mut users: Vec<ListedUser> = users
+        .into_iter()
+        .filter(|user| filter.allows(user))
+        .map(|user| {
+            let last_login = user
+                .name()
+                .and_then(|name| last_logins.get(&name, user.uid()));
+            ListedUser { user, last_login }
+        })
+        .collect();
+
+    match config.order {
+        UserOrder::Lightdm => {}
+        UserOrder::Alphabetical => users.sort_by_cached_key(|listed| {
+            let user = &listed.user;
+            user.display_name()
+                .or_else(|| user.name())
+                .map(|name| name.to_lowercase())
+        }),
+        UserOrder::Recent => users.sort_by_key(|listed| Reverse(listed.last_login)),
+    }
+    // The sort is stable, the users which are not pinned keep the order above
+    users.sort_by_key(|listed| {
+        let name = listed.user.name();
+        config
+            .pinned
+            .iter()
+            .position(|pinned| name.as_deref() == Some(pinned.as_str()))
+            .unwrap_or(usize::MAX)
+    });
+    users
+}
+
+/// The content of a database such as /etc/passwd, empty if it can not be read
+fn read_database(path: &str) -> String {
+    std::fs::read_to_string(path).unwrap_or_else(|e| {
+        logger_warn!("Could not read {path}: {e}");
+        String::new()
+    })
+}
+
+/// Entries of a colon separated database, comments and NIS entries skipped
+fn parse_entries(content: &str) -> Vec<Vec<String>> {
+    content
+        .lines()
+        .filter(|line| !line.is_empty() && !line.starts_with(['#', '+', '-']))
+        .map(|line| line.split(':').map(str::to_string).collect())
+        .collect()
+}
+
+fn parse_passwd(content: &str) -> HashMap<String, Account> {
+    parse_entries(content)
+        .into_iter()
+        .filter_map(|entry| match entry.as_slice() {
+            [name, _, uid, gid, _, _, shell] => Some((
+                name.clone(),
+                Account {
+                    uid: uid.parse().ok()?,
+                    gid: gid.parse().ok()?,
+                    shell: shell.clone(),
+                },
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+fn parse_group(content: &str) -> HashMap<String, Group> {
+    parse_entries(content)
+        .into_iter()
+        .filter_map(|entry| match entry.as_slice() {
+            [name, _, gid, members] => Some((
+                name.clone(),
+                Group {
+                    gid: gid.parse().ok()?,
+                    members: members
+                        .split(',')
+                        .filter(|member| !member.is_empty())
+                        .map(str::to_string)
+                        .collect(),
+                },
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::UidRange;
+
+    const PASSWD: &str = "\
+root:x:0:0:root:/root:/bin/bash
+# A comment
+alice:x:1000:1000:Alice:/home/alice:/bin/bash
+bob:x:1001:100:Bob:/home/bob:/usr/bin/zsh
+carol:x:1002:1002:Carol:/home/carol:/bin/bash
+daemon:x:2:2:daemon:/sbin:/usr/sbin/nologin
+broken:x:notanumber:1003::/home/broken:/bin/bash
++nisuser::::::
+";
+
+    const GROUP: &str = "\
+root:x:0:
+users:x:100:
+wheel:x:10:carol,
+alice:x:1000:
+";
+
+    /// Which of the users of the databases `config` allows
+    fn allowed(config: &Users) -> Vec<&'static str> {
+        let filter = UserFilter::with_databases(config, PASSWD, GROUP);
+        [
+            "root", "alice", "bob", "carol", "daemon", "broken", "ldapuser",
+        ]
+        .into_iter()
+        .filter(|username| filter.allows_name(username))
+        .collect()
+    }
+
+    fn users(configure: impl FnOnce(&mut Users)) -> Users {
+        let mut config = Users::default();
+        configure(&mut config);
+        config
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn databases_are_parsed() {
+        let accounts = parse_passwd(PASSWD);
+        assert_eq!(accounts.len(), 5);
+        let bob = &accounts["bob"];
+        assert_eq!(
+            (bob.uid, bob.gid, bob.shell.as_str()),
+            (1001, 100, "/usr/bin/zsh")
+        );
+        assert!(!accounts.contains_key("broken"));
+        assert!(!accounts.contains_key("+nisuser"));
+
+        let groups = parse_group(GROUP);
+        assert_eq!(groups["wheel"].gid, 10);
+        assert_eq!(groups["wheel"].members, ["carol"]);
+        assert!(groups["users"].members.is_empty());
+    }
+
+    #[test]
+    fn no_filter_allows_everybody() {
+        assert_eq!(
+            allowed(&Users::default()),
+            [
+                "root", "alice", "bob", "carol", "daemon", "broken", "ldapuser"
+            ]
+        );
+    }
+
+    #[test]
+    fn filters() {
+        let cases = [
+            (
+                users(|c| c.include = names(&["alice", "ldapuser"])),
+                vec!["alice", "ldapuser"],
+            ),
+            (
+                users(|c| c.exclude = names(&["root", "bob"])),
+                vec!["alice", "carol", "daemon", "broken", "ldapuser"],
+            ),
+            // Excluding wins over including
+            (
+                users(|c| {
+                    c.include = names(&["alice", "bob"]);
+                    c.exclude = names(&["bob"]);
+                }),
+                vec!["alice"],
+            ),
+            // Users without a known UID are never in a range
+            (
+                users(|c| {
+                    c.uid_ranges = vec![UidRange {
+                        min: 1000,
+                        max: 1001,
+                    }]
+                }),
+                vec!["alice", "bob"],
+            ),
+            (
+                users(|c| {
+                    c.uid_ranges = vec![
+                        UidRange { min: 0, max: 0 },
+                        UidRange {
+                            min: 1002,
+                            max: 60000,
+                        },
+                    ]
+                }),
+                vec!["root", "carol"],
+            ),
+            // Unknown shells are not hidden
+            (
+                users(|c| c.hidden_shells = names(&["/usr/sbin/nologin", "/usr/bin/zsh"])),
+                vec!["root", "alice", "carol", "broken", "ldapuser"],
+            ),
+            // Supplementary members and primary groups
+            (
+                users(|c| c.groups = names(&["wheel", "users"])),
+                vec!["bob", "carol"],
+            ),
+            (users(|c| c.groups = names(&["alice"])), vec!["alice"]),
+            (users(|c| c.groups = names(&["nogroup"])), vec![]),
+            // All the filters apply
+            (
+                users(|c| {
+                    c.uid_ranges = vec![UidRange {
+                        min: 1000,
+                        max: 60000,
+                    }];
+                    c.groups = names(&["wheel", "users", "alice"]);
+                    c.hidden_shells = names(&["/usr/bin/zsh"]);
+                }),
+                vec!["alice", "carol"],
+            ),
+        ];
+        for (config, expected) in cases {
+            assert_eq!(allowed(&config), expected, "{config:?}");
+        }
+    }
+}
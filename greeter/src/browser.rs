@@ -11,9 +11,16 @@ use gtk::{
 };
 use webkit::prelude::WebViewExt;
 
-use std::{cell::Cell, rc::Rc};
+use std::{
+    cell::Cell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
-use crate::bridge::Dispatcher;
+use crate::{
+    bridge::Dispatcher,
+    keybindings::{Action, Profile},
+};
 
 pub struct BrowserProperties {
     pub id: u64,
@@ -44,6 +51,10 @@ impl Browser {
         self.properties.is_primary
     }
 
+    pub fn window(&self) -> Option<gtk::Window> {
+        self.webview.root().and_downcast::<gtk::Window>()
+    }
+
     pub fn connect_user_message_received(&self, dispatcher: Rc<Dispatcher>) {
         let win_props = &self.properties;
         let loaded = &self.loaded;
@@ -67,12 +78,44 @@ impl Browser {
     }
 }
 
+/// Fade the windows of `browsers` to `opacity` in `duration` milliseconds, then call `done`
+pub fn fade(browsers: &[Browser], opacity: f64, duration: u32, done: impl FnOnce() + 'static) {
+    const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+    let windows: Vec<gtk::Window> = browsers.iter().filter_map(Browser::window).collect();
+    if duration == 0 || windows.is_empty() {
+        windows
+            .iter()
+            .for_each(|window| window.set_opacity(opacity));
+        done();
+        return;
+    }
+
+    let from: Vec<f64> = windows.iter().map(|window| window.opacity()).collect();
+    let start = Instant::now();
+    let mut done = Some(done);
+    glib::timeout_add_local(FRAME_INTERVAL, move || {
+        let progress = (start.elapsed().as_millis() as f64 / duration as f64).min(1.0);
+        for (window, from) in windows.iter().zip(&from) {
+            window.set_opacity(from + (opacity - from) * progress);
+        }
+        if progress < 1.0 {
+            return glib::ControlFlow::Continue;
+        }
+        if let Some(done) = done.take() {
+            done();
+        }
+        glib::ControlFlow::Break
+    });
+}
+
 #[must_use = "The builder must be built to be used"]
 pub struct BrowserBuilder {
     id: u64,
     window: Option<gtk::ApplicationWindow>,
     webview: Option<webkit::WebView>,
     geometry: Option<Rectangle>,
+    keybindings: Profile,
     debug_mode: bool,
     is_primary: bool,
 }
@@ -84,6 +127,7 @@ impl BrowserBuilder {
             window: None,
             webview: None,
             geometry: None,
+            keybindings: Default::default(),
             debug_mode: false,
             is_primary: false,
         }
@@ -119,6 +163,11 @@ impl BrowserBuilder {
         self
     }
 
+    pub fn keybindings(mut self, keybindings: &Profile) -> Self {
+        self.keybindings = keybindings.clone();
+        self
+    }
+
     #[must_use = "Building the object from the builder is usually expensive and is not expected to have side effects"]
     pub fn build(self) -> Browser {
         if self.window.is_none() || self.webview.is_none() {
@@ -132,7 +181,7 @@ impl BrowserBuilder {
         let is_primary = self.is_primary;
 
         setup_style(&window, geometry, debug_mode);
-        setup_actions(&window, &webview, debug_mode);
+        setup_actions(&window, &webview, debug_mode, &self.keybindings);
         window.set_child(Some(&webview));
         Browser {
             webview,
@@ -153,100 +202,140 @@ fn setup_style(window: &gtk::ApplicationWindow, geometry: Rectangle, debug: bool
     window.set_fullscreened(!debug);
 }
 
-fn setup_actions(window: &gtk::ApplicationWindow, webview: &webkit::WebView, debug: bool) {
+fn setup_actions(
+    window: &gtk::ApplicationWindow,
+    webview: &webkit::WebView,
+    debug: bool,
+    keybindings: &Profile,
+) {
     let win_entries = [
-        gio::ActionEntry::builder("undo")
-            .activate(clone!(
-                #[weak]
-                webview,
-                move |_, _, _| webview.execute_editing_command(webkit::EDITING_COMMAND_UNDO)
-            ))
-            .build(),
-        gio::ActionEntry::builder("redo")
-            .activate(clone!(
-                #[weak]
-                webview,
-                move |_, _, _| webview.execute_editing_command(webkit::EDITING_COMMAND_REDO)
-            ))
-            .build(),
-        gio::ActionEntry::builder("copy")
-            .activate(clone!(
-                #[weak]
-                webview,
-                move |_, _, _| webview.execute_editing_command(webkit::EDITING_COMMAND_COPY)
-            ))
-            .build(),
-        gio::ActionEntry::builder("cut")
-            .activate(clone!(
-                #[weak]
-                webview,
-                move |_, _, _| webview.execute_editing_command(webkit::EDITING_COMMAND_CUT)
-            ))
-            .build(),
-        gio::ActionEntry::builder("paste")
-            .activate(clone!(
-                #[weak]
-                webview,
-                move |_, _, _| webview.execute_editing_command(webkit::EDITING_COMMAND_PASTE)
-            ))
-            .build(),
-        gio::ActionEntry::builder("paste-plain")
-            .activate(clone!(
-                #[weak]
-                webview,
-                move |_, _, _| webview
-                    .execute_editing_command(webkit::EDITING_COMMAND_PASTE_AS_PLAIN_TEXT)
-            ))
-            .build(),
-        gio::ActionEntry::builder("select-all")
-            .activate(clone!(
-                #[weak]
-                webview,
-                move |_, _, _| webview.execute_editing_command(webkit::EDITING_COMMAND_SELECT_ALL)
-            ))
-            .build(),
-        gio::ActionEntry::builder("zoom-normal")
-            .activate(clone!(
-                #[weak]
-                webview,
-                move |_, _, _| webview.set_zoom_level(1f64)
-            ))
-            .build(),
-        gio::ActionEntry::builder("zoom-in")
-            .activate(clone!(
-                #[weak]
-                webview,
-                move |_, _, _| webview.set_zoom_level(get_zoom_next_level(webview.zoom_level(), 1))
-            ))
-            .build(),
-        gio::ActionEntry::builder("zoom-out")
-            .activate(clone!(
-                #[weak]
-                webview,
-                move |_, _, _| webview
-                    .set_zoom_level(get_zoom_next_level(webview.zoom_level(), -1))
-            ))
-            .build(),
-        gio::ActionEntry::builder("reload")
-            .activate(clone!(
-                #[weak]
-                webview,
-                move |_, _, _| webview.reload()
-            ))
-            .build(),
-        gio::ActionEntry::builder("force-reload")
-            .activate(clone!(
-                #[weak]
-                webview,
-                move |_, _, _| webview.reload_bypass_cache()
-            ))
-            .build(),
-    ];
-    window.add_action_entries(win_entries);
-
-    if debug {
-        let win_debug_entries = [
-            gio::ActionEntry::builder("toggle-inspector")
+        (
+            Action::Undo,
+            gio::ActionEntry::builder(Action::Undo.name())
+                .activate(clone!(
+                    #[weak]
+                    webview,
+                    move |_, _, _| webview.execute_editing_command(webkit::EDITING_COMMAND_UNDO)
+                ))
+                .build(),
+        ),
+        (
+            Action::Redo,
+            gio::ActionEntry::builder(Action::Redo.name())
+                .activate(clone!(
+                    #[weak]
+                    webview,
+                    move |_, _, _| webview.execute_editing_command(webkit::EDITING_COMMAND_REDO)
+                ))
+                .build(),
+        ),
+        (
+            Action::Copy,
+            gio::ActionEntry::builder(Action::Copy.name())
+                .activate(clone!(
+                    #[weak]
+                    webview,
+                    move |_, _, _| webview.execute_editing_command(webkit::EDITING_COMMAND_COPY)
+                ))
+                .build(),
+        ),
+        (
+            Action::Cut,
+            gio::ActionEntry::builder(Action::Cut.name())
+                .activate(clone!(
+                    #[weak]
+                    webview,
+                    move |_, _, _| webview.execute_editing_command(webkit::EDITING_COMMAND_CUT)
+                ))
+                .build(),
+        ),
+        (
+            Action::Paste,
+            gio::ActionEntry::builder(Action::Paste.name())
+                .activate(clone!(
+                    #[weak]
+                    webview,
+                    move |_, _, _| webview.execute_editing_command(webkit::EDITING_COMMAND_PASTE)
+                ))
+                .build(),
+        ),
+        (
+            Action::PastePlain,
+            gio::ActionEntry::builder(Action::PastePlain.name())
+                .activate(clone!(
+                    #[weak]
+                    webview,
+                    move |_, _, _| webview
+                        .execute_editing_command(webkit::EDITING_COMMAND_PASTE_AS_PLAIN_TEXT)
+                ))
+                .build(),
+        ),
+        (
+            Action::SelectAll,
+            gio::ActionEntry::builder(Action::SelectAll.name())
+                .activate(clone!(
+                    #[weak]
+                    webview,
+                    move |_, _, _| webview
+                        .execute_editing_command(webkit::EDITING_COMMAND_SELECT_ALL)
+                ))
+                .build(),
+        ),
+        (
+            Action::ZoomNormal,
+            gio::ActionEntry::builder(Action::ZoomNormal.name())
+                .activate(clone!(
+                    #[weak]
+                    webview,
+                    move |_, _, _| webview.set_zoom_level(1f64)
+                ))
+                .build(),
+        ),
+        (
+            Action::ZoomIn,
+            gio::ActionEntry::builder(Action::ZoomIn.name())
+                .activate(clone!(
+                    #[weak]
+                    webview,
+                    move |_, _, _| webview
+                        .set_zoom_level(get_zoom_next_level(webview.zoom_level(), 1))
+                ))
+                .build(),
+        ),
+        (
+            Action::ZoomOut,
+            gio::ActionEntry::builder(Action::ZoomOut.name())
+                .activate(clone!(
+                    #[weak]
+                    webview,
+                    move |_, _, _| webview
+                        .set_zoom_level(get_zoom_next_level(webview.zoom_level(), -1))
+                ))
+                .build(),
+        ),
+        (
+            Action::Reload,
+            gio::ActionEntry::builder(Action::Reload.name())
+                .activate(clone!(
+                    #[weak]
+                    webview,
+                    move |_, _, _| webview.reload()
+                ))
+                .build(),
+        ),
+        (
+            Action::ForceReload,
+            gio::ActionEntry::builder(Action::ForceReload.name())
+                .activate(clone!(
+                    #[weak]
+                    webview,
+                    move |_, _, _| webview.reload_bypass_cache()
+                ))
+                .build(),
+        ),
+        (
+            Action::ToggleInspector,
+            gio::ActionEntry::builder(Action::ToggleInspector.name())
                 .activate(clone!(
                     #[weak]
                     webview,
@@ -260,7 +349,10 @@ fn setup_actions(window: &gtk::ApplicationWindow, webview: &webkit::WebView, deb
                     }
                 ))
                 .build(),
-            gio::ActionEntry::builder("fullscreen")
+        ),
+        (
+            Action::Fullscreen,
+            gio::ActionEntry::builder(Action::Fullscreen.name())
                 .activate(|window: &ApplicationWindow, _, _| {
                     let is_fullscreend = window.is_fullscreen();
                     if is_fullscreend {
@@ -271,15 +363,28 @@ fn setup_actions(window: &gtk::ApplicationWindow, webview: &webkit::WebView, deb
                     window.set_fullscreened(!is_fullscreend);
                 })
                 .build(),
-            gio::ActionEntry::builder("close")
+        ),
+        (
+            Action::Close,
+            gio::ActionEntry::builder(Action::Close.name())
                 .activate(|window: &ApplicationWindow, _, _| window.close())
                 .build(),
-            gio::ActionEntry::builder("minimize")
+        ),
+        (
+            Action::Minimize,
+            gio::ActionEntry::builder(Action::Minimize.name())
                 .activate(|window: &ApplicationWindow, _, _| window.minimize())
                 .build(),
-        ];
-        window.add_action_entries(win_debug_entries);
-    }
+        ),
+    ];
+
+    window.add_action_entries(
+        win_entries
+            .into_iter()
+            .filter(|(action, _)| debug || !action.debug_only())
+            .filter(|(action, _)| keybindings.contains_key(action))
+            .map(|(_, entry)| entry),
+    );
 }
 
 const ZOOM_LEVELS: [f64; 12] = [
@@ -0,0 +1,219 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeMap;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Action {
+    #[serde(rename = "app.quit")]
+    Quit,
+    #[serde(rename = "win.toggle-inspector")]
+    ToggleInspector,
+    #[serde(rename = "win.undo")]
+    Undo,
+    #[serde(rename = "win.redo")]
+    Redo,
+    #[serde(rename = "win.cut")]
+    Cut,
+    #[serde(rename = "win.copy")]
+    Copy,
+    #[serde(rename = "win.paste")]
+    Paste,
+    #[serde(rename = "win.paste-plain")]
+    PastePlain,
+    #[serde(rename = "win.select-all")]
+    SelectAll,
+    #[serde(rename = "win.zoom-normal")]
+    ZoomNormal,
+    #[serde(rename = "win.zoom-in")]
+    ZoomIn,
+    #[serde(rename = "win.zoom-out")]
+    ZoomOut,
+    #[serde(rename = "win.fullscreen")]
+    Fullscreen,
+    #[serde(rename = "win.reload")]
+    Reload,
+    #[serde(rename = "win.force-reload")]
+    ForceReload,
+    #[serde(rename = "win.close")]
+    Close,
+    #[serde(rename = "win.minimize")]
+    Minimize,
+}
+
+impl Action {
+    /// Detailed action name, as used by `set_accels_for_action`
+    pub fn detailed_name(&self) -> &'static str {
+        match self {
+            Action::Quit => "app.quit",
+            Action::ToggleInspector => "win.toggle-inspector",
+            Action::Undo => "win.undo",
+            Action::Redo => "win.redo",
+            Action::Cut => "win.cut",
+            Action::Copy => "win.copy",
+            Action::Paste => "win.paste",
+            Action::PastePlain => "win.paste-plain",
+            Action::SelectAll => "win.select-all",
+            Action::ZoomNormal => "win.zoom-normal",
+            Action::ZoomIn => "win.zoom-in",
+            Action::ZoomOut => "win.zoom-out",
+            Action::Fullscreen => "win.fullscreen",
+            Action::Reload => "win.reload",
+            Action::ForceReload => "win.force-reload",
+            Action::Close => "win.close",
+            Action::Minimize => "win.minimize",
+        }
+    }
+
+    /// Action name without the `app.`/`win.` prefix, as used by `ActionEntry`
+    pub fn name(&self) -> &'static str {
+        let detailed_name = self.detailed_name();
+        detailed_name
+            .split_once('.')
+            .map_or(detailed_name, |(_, name)| name)
+    }
+
+    /// Actions that are only registered when the greeter runs in debug mode
+    pub fn debug_only(&self) -> bool {
+        matches!(
+            self,
+            Action::ToggleInspector | Action::Fullscreen | Action::Close | Action::Minimize
+        )
+    }
+}
+
+pub type Profile = BTreeMap<Action, Vec<String>>;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Keybindings {
+    debug: Profile,
+    normal: Profile,
+}
+
+impl Default for Keybindings {
+    fn default() -> Self {
+        let editing = [
+            (Action::Undo, vec!["<Ctl>Z"]),
+            (Action::Redo, vec!["<Ctl><Shift>Z"]),
+            (Action::Cut, vec!["<Ctl>X"]),
+            (Action::Copy, vec!["<Ctl>C"]),
+            (Action::Paste, vec!["<Ctl>V"]),
+            (Action::PastePlain, vec!["<Ctl><Shift>V"]),
+            (Action::SelectAll, vec!["<Ctl>A"]),
+            (Action::ZoomNormal, vec!["<Ctl>0", "<Ctl>KP_0"]),
+            (
+                Action::ZoomIn,
+                vec!["<Ctl>plus", "<Ctl>equal", "<Ctl>KP_Add", "ZoomIn"],
+            ),
+            (
+                Action::ZoomOut,
+                vec!["<Ctl>minus", "<Ctl>KP_Subtract", "ZoomOut"],
+            ),
+        ];
+        let debug_only = [
+            (Action::Quit, vec!["<Ctl>Q"]),
+            (Action::ToggleInspector, vec!["<Ctl><Shift>I", "F12"]),
+            (Action::Fullscreen, vec!["F11"]),
+            (Action::Reload, vec!["<Ctl>R", "F5", "Refresh", "Reload"]),
+            (Action::ForceReload, vec!["<Ctl><Shift>R", "<Shift>F5"]),
+            (Action::Close, vec!["<Ctl>W"]),
+            (Action::Minimize, vec!["<Ctl>M"]),
+        ];
+
+        let to_profile = |entries: &[(Action, Vec<&str>)]| -> Profile {
+            entries
+                .iter()
+                .map(|(action, accels)| (*action, accels.iter().map(|s| s.to_string()).collect()))
+                .collect()
+        };
+
+        let normal = to_profile(&editing);
+        let mut debug = normal.clone();
+        debug.extend(to_profile(&debug_only));
+
+        Self { debug, normal }
+    }
+}
+
+impl Keybindings {
+    pub fn profile(&self, debug: bool) -> &Profile {
+        if debug { &self.debug } else { &self.normal }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ACTIONS: [Action; 17] = [
+        Action::Quit,
+        Action::ToggleInspector,
+        Action::Undo,
+        Action::Redo,
+        Action::Cut,
+        Action::Copy,
+        Action::Paste,
+        Action::PastePlain,
+        Action::SelectAll,
+        Action::ZoomNormal,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::Fullscreen,
+        Action::Reload,
+        Action::ForceReload,
+        Action::Close,
+        Action::Minimize,
+    ];
+
+    #[test]
+    fn names_match_serde() {
+        for action in ACTIONS {
+            let serialized = serde_json::to_value(action).unwrap();
+            assert_eq!(serialized.as_str(), Some(action.detailed_name()));
+            assert_eq!(
+                serde_json::from_value::<Action>(serialized).unwrap(),
+                action
+            );
+
+            let (prefix, name) = action.detailed_name().split_once('.').unwrap();
+            assert!(matches!(prefix, "app" | "win"), "{prefix}");
+            assert_eq!(action.name(), name);
+        }
+    }
+
+    #[test]
+    fn normal_profile_leaves_quit_and_reload_unbound() {
+        let keybindings = Keybindings::default();
+        let normal = keybindings.profile(false);
+        for action in [Action::Quit, Action::Reload, Action::ForceReload] {
+            assert!(!normal.contains_key(&action), "{action:?}");
+            assert!(
+                keybindings.profile(true).contains_key(&action),
+                "{action:?}"
+            );
+        }
+        assert!(!normal.keys().any(Action::debug_only));
+    }
+
+    #[test]
+    fn unknown_actions_are_rejected() {
+        let yaml = "normal:\n  win.self-destruct: [\"<Ctl>D\"]\n";
+        assert!(serde_yaml_ng::from_str::<Keybindings>(yaml).is_err());
+        // Actions are named with their prefix
+        let yaml = "normal:\n  quit: [\"<Ctl>Q\"]\n";
+        assert!(serde_yaml_ng::from_str::<Keybindings>(yaml).is_err());
+
+        let yaml = "normal:\n  app.quit: [\"<Ctl>Q\"]\n";
+        let keybindings = serde_yaml_ng::from_str::<Keybindings>(yaml).unwrap();
+        assert_eq!(keybindings.profile(false)[&Action::Quit], ["<Ctl>Q"]);
+        // The other profile keeps its default
+        assert_eq!(
+            keybindings.profile(true),
+            Keybindings::default().profile(true)
+        );
+    }
+}
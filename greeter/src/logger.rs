@@ -8,6 +8,12 @@ macro_rules! logger_debug {
     }
 }
 
+macro_rules! logger_info {
+    ($($arg:tt)*) => {
+        logger_raw!("INFO", $($arg)*);
+    }
+}
+
 macro_rules! logger_warn {
     ($($arg:tt)*) => {
         logger_raw!("WARN", $($arg)*);
@@ -7,14 +7,17 @@ mod logger;
 mod application;
 mod bridge;
 mod browser;
+mod keybindings;
 mod settings;
 mod theme;
+mod watcher;
 mod webview;
 
 use gtk::prelude::*;
 use gtk::{gio, glib};
 
 use crate::application::{on_activate, on_startup};
+use crate::settings::{Settings, check_configuration, dump_configuration};
 use crate::theme::print_themes;
 
 fn main() -> glib::ExitCode {
@@ -24,6 +27,33 @@ fn main() -> glib::ExitCode {
         return glib::ExitCode::SUCCESS;
     }
 
+    if let Some(Command::Api(ApiCommand::Typings)) = &args.command {
+        print!("{}", protocol::typescript());
+        return glib::ExitCode::SUCCESS;
+    }
+
+    if let Some(Command::Config(command)) = &args.command {
+        return match command {
+            ConfigCommand::Check => {
+                let diagnostics = check_configuration(args.config());
+                diagnostics.iter().for_each(|d| println!("{d}"));
+                if diagnostics.is_empty() {
+                    println!("Configuration OK");
+                    glib::ExitCode::SUCCESS
+                } else {
+                    glib::ExitCode::FAILURE
+                }
+            }
+            ConfigCommand::Dump => {
+                print!(
+                    "{}",
+                    dump_configuration(args.config(), args.debug_mode(), args.theme())
+                );
+                glib::ExitCode::SUCCESS
+            }
+        };
+    }
+
     gio::resources_register_include!("greeter.gresource").expect("Failed to register resources.");
 
     let webinfo = webkit::ApplicationInfo::new();
@@ -34,17 +64,17 @@ fn main() -> glib::ExitCode {
         .flags(Default::default())
         .build();
 
-    let debug = args.debug_mode();
-    let theme = args.theme().map(|s| s.to_string());
-    app.connect_activate(move |app| on_activate(app, debug, theme.as_deref()));
-    app.connect_startup(on_startup);
+    let config = Settings::new(args.config(), args.debug_mode(), args.theme());
+    let keybindings = config.keybindings().clone();
+    app.connect_activate(move |app| on_activate(app, config.clone()));
+    app.connect_startup(move |app| on_startup(app, &keybindings));
 
     let exit_code = app.run_with_args::<glib::GString>(&[]);
     logger_debug!("LightDM WebKit Greeter stopped");
     exit_code
 }
 
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
 enum Mode {
     Debug,
@@ -67,9 +97,38 @@ struct CliArgs {
     /// Theme
     #[arg(long)]
     theme: Option<String>,
+    /// Config file, drop-ins are read from the sibling `.d` directory
+    #[arg(long, value_name = "PATH")]
+    config: Option<String>,
     /// List installed themes
     #[arg(long)]
     list: bool,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Inspect the configuration
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Describe the theme API
+    #[command(subcommand)]
+    Api(ApiCommand),
+}
+
+#[derive(Debug, Subcommand)]
+enum ConfigCommand {
+    /// Report unknown keys, type errors and invalid values
+    Check,
+    /// Print the effective settings
+    Dump,
+}
+
+#[derive(Debug, Subcommand)]
+enum ApiCommand {
+    /// Print TypeScript declarations of the theme API
+    Typings,
 }
 
 impl CliArgs {
@@ -80,4 +139,8 @@ impl CliArgs {
     fn theme(&self) -> Option<&str> {
         self.theme.as_deref()
     }
+
+    fn config(&self) -> Option<&str> {
+        self.config.as_deref()
+    }
 }
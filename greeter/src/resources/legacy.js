@@ -0,0 +1,67 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Compatibility layer for themes written for the lightdm-webkit2-greeter API.
+// Themes opt in with `legacy_api: true` in their index.yml.
+
+const LEGACY_PROMPT_TYPES = ["text", "password"];
+const LEGACY_MESSAGE_TYPES = ["info", "error"];
+
+const install_legacy_api = (lightdm) => {
+  Object.defineProperties(lightdm, {
+    num_users: { get: () => lightdm.users.length },
+    timed_login_user: { get: () => lightdm.autologin_user },
+    timed_login_delay: { get: () => lightdm.autologin_timeout },
+    default_language: { get: () => lightdm.language },
+    default_layout: { get: () => lightdm.layout },
+    default_xsession: { get: () => lightdm.default_session },
+  });
+
+  /**
+   * @deprecated Use {@link LightDMGreeter#authenticate}
+   */
+  lightdm.start_authentication = (username) => lightdm.authenticate(username);
+  /**
+   * @deprecated Use {@link LightDMGreeter#respond}
+   */
+  lightdm.provide_secret = (secret) => lightdm.respond(secret);
+  /**
+   * @deprecated Use {@link LightDMGreeter#start_session}
+   */
+  lightdm.login = (_user, session) => lightdm.start_session(session);
+  /**
+   * @deprecated Use {@link LightDMGreeter#cancel_autologin}
+   */
+  lightdm.cancel_timed_login = () => lightdm.cancel_autologin();
+
+  // Legacy themes define global callbacks instead of connecting to signals.
+  // Subframes do not get the main frame only signals
+  lightdm.show_prompt?.connect((text, type) =>
+    window.show_prompt?.(text, LEGACY_PROMPT_TYPES[type] ?? "text"),
+  );
+  lightdm.show_message?.connect((text, type) =>
+    window.show_message?.(text, LEGACY_MESSAGE_TYPES[type] ?? "info"),
+  );
+  lightdm.authentication_complete?.connect(() =>
+    window.authentication_complete?.(),
+  );
+  lightdm.autologin_timer_expired?.connect(() =>
+    window.autologin_timer_expired?.(),
+  );
+
+  window.config = {
+    get_str: (section, key) => window.greeter_config[section]?.[key] ?? null,
+    get_num: (section, key) =>
+      Number(window.greeter_config[section]?.[key] ?? 0),
+    get_bool: (section, key) =>
+      Boolean(window.greeter_config[section]?.[key] ?? false),
+  };
+  window.greeterutil = {
+    dirlist: (path) => window.theme_utils.dirlist(path, false, () => {}),
+  };
+};
+
+if (window.greeter_config.theme?.legacy_api) {
+  install_legacy_api(window.lightdm);
+}
@@ -0,0 +1,39 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+// Evaluated before the native theme API objects are created, which get a signal per event.
+// Signals are frozen along with them, the callbacks are private so that they can't be read.
+
+class LightDMSignal {
+  #name;
+  #callbacks;
+  constructor(name) {
+    this.#name = name;
+    this.#callbacks = [];
+  }
+  get name() {
+    return this.#name;
+  }
+  connect(callback) {
+    if (typeof callback === "function") {
+      this.#callbacks.push(callback);
+    }
+  }
+  disconnect(callback) {
+    if (typeof callback === "function") {
+      this.#callbacks = this.#callbacks.filter((_cb) => _cb !== callback);
+    }
+  }
+  emit(...args) {
+    // Callbacks connected or disconnected by a callback take effect from the next emission,
+    // one throwing doesn't keep the others from being called
+    for (const callback of [...this.#callbacks]) {
+      try {
+        callback(...args);
+      } catch (e) {
+        reportError(e);
+      }
+    }
+  }
+}
@@ -0,0 +1,353 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use lightdm::prelude::*;
+use serde_yaml_ng::Value;
+
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use super::{
+    ConfigError, DEFAULT_CONFIG_PATH, Settings, Theme, config_sources, read_configuration,
+};
+use crate::theme::DEFAULT_THEMES_DIR;
+
+/// Mappings whose keys are validated by deserialization rather than against the defaults
+const DYNAMIC_MAPPINGS: [&str; 2] = ["keybindings.debug", "keybindings.normal"];
+
+pub struct Diagnostic {
+    file: PathBuf,
+    line: Option<usize>,
+    message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{line}: {}", self.file.display(), self.message),
+            None => write!(f, "{}: {}", self.file.display(), self.message),
+        }
+    }
+}
+
+/// Check every config source and the effective settings they produce
+pub fn check_configuration(config: Option<&str>) -> Vec<Diagnostic> {
+    let path_to_config = Path::new(config.unwrap_or(DEFAULT_CONFIG_PATH));
+    let mut diagnostics = vec![];
+    let mut sources: Vec<(PathBuf, String)> = vec![];
+    // The greeter would go on without a source it can't read, which is still worth reporting
+    for source in config_sources(path_to_config) {
+        match std::fs::read_to_string(&source) {
+            Ok(content) => sources.push((source, content)),
+            Err(e) => diagnostics.push(Diagnostic {
+                file: source,
+                line: None,
+                message: format!("can not be read: {e}"),
+            }),
+        }
+    }
+
+    let schema = Settings {
+        theme: Some(Theme {
+            secondary_html: Some(String::new()),
+            min_api_version: Some(String::new()),
+            ..Default::default()
+        }),
+        themes_dir: Some(DEFAULT_THEMES_DIR.to_string()),
+        ..Default::default()
+    };
+    let schema = serde_yaml_ng::to_value(&schema).expect("Settings is serializable");
+
+    for (source, content) in &sources {
+        let value = match serde_yaml_ng::from_str::<Value>(content) {
+            Ok(value) => value,
+            Err(e) => {
+                diagnostics.push(Diagnostic {
+                    file: source.clone(),
+                    line: e.location().map(|l| l.line()),
+                    message: e.to_string(),
+                });
+                continue;
+            }
+        };
+        if value.is_null() {
+            continue;
+        }
+
+        let found = diagnostics.len();
+        check_value(
+            &value,
+            &schema,
+            &mut vec![],
+            source,
+            content,
+            &mut diagnostics,
+        );
+        if found == diagnostics.len()
+            && let Err(e) = serde_yaml_ng::from_str::<Settings>(content)
+        {
+            diagnostics.push(Diagnostic {
+                file: source.clone(),
+                line: e.location().map(|l| l.line()),
+                message: e.to_string(),
+            });
+        }
+    }
+    if !diagnostics.is_empty() {
+        return diagnostics;
+    }
+
+    let settings = match read_configuration(path_to_config) {
+        Ok(settings) => settings,
+        Err(ConfigError::Read(path, e)) => {
+            diagnostics.push(Diagnostic {
+                file: path,
+                line: None,
+                message: e.to_string(),
+            });
+            return diagnostics;
+        }
+        Err(ConfigError::Parse(path, e)) => {
+            diagnostics.push(Diagnostic {
+                file: path,
+                line: None,
+                message: e.to_string(),
+            });
+            return diagnostics;
+        }
+    };
+
+    for (key, message) in settings.validate() {
+        let segments: Vec<&str> = key.split('.').collect();
+        let (file, line) = sources
+            .iter()
+            .rev()
+            .find_map(|(source, content)| {
+                line_of(content, &segments).map(|line| (source.clone(), Some(line)))
+            })
+            .unwrap_or_else(|| (path_to_config.to_path_buf(), None));
+        diagnostics.push(Diagnostic {
+            file,
+            line,
+            message: format!("{key}: {message}"),
+        });
+    }
+    diagnostics
+}
+
+fn check_value(
+    value: &Value,
+    schema: &Value,
+    path: &mut Vec<String>,
+    source: &Path,
+    content: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if schema.is_null() || value.is_null() {
+        return;
+    }
+
+    let line = |path: &[String]| {
+        let segments: Vec<&str> = path.iter().map(|s| s.as_str()).collect();
+        line_of(content, &segments)
+    };
+
+    match (schema, value) {
+        (Value::Mapping(schema), Value::Mapping(value)) => {
+            if DYNAMIC_MAPPINGS.contains(&path.join(".").as_str()) {
+                return;
+            }
+            for (key, value) in value {
+                let key = match key.as_str() {
+                    Some(key) => key.to_string(),
+                    None => format!("{key:?}"),
+                };
+                path.push(key.clone());
+                match schema.get(&key) {
+                    Some(schema) => check_value(value, schema, path, source, content, diagnostics),
+                    None => diagnostics.push(Diagnostic {
+                        file: source.to_path_buf(),
+                        line: line(path),
+                        message: format!("unknown key `{}`", path.join(".")),
+                    }),
+                }
+                path.pop();
+            }
+        }
+        (schema, value) if kind(schema) != kind(value) => diagnostics.push(Diagnostic {
+            file: source.to_path_buf(),
+            line: line(path),
+            message: format!(
+                "{}: invalid type {}, expected {}",
+                path.join("."),
+                kind(value),
+                kind(schema)
+            ),
+        }),
+        _ => {}
+    }
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Sequence(_) => "a sequence",
+        Value::Mapping(_) => "a mapping",
+        Value::Tagged(_) => "a tagged value",
+    }
+}
+
+/// 1-based line where the block-style key `path` is defined in `content`
+fn line_of(content: &str, path: &[&str]) -> Option<usize> {
+    let mut lines = content.lines().enumerate();
+    let mut parent_indent = None;
+    let mut found = None;
+    for segment in path {
+        let (idx, indent) = lines.by_ref().find_map(|(idx, line)| {
+            let trimmed = line.trim_start();
+            let indent = line.len() - trimmed.len();
+            let is_key = trimmed
+                .trim_start_matches(['"', '\''])
+                .strip_prefix(segment)
+                .is_some_and(|rest| rest.trim_start_matches(['"', '\'']).starts_with(':'));
+            (is_key && parent_indent.is_none_or(|parent| indent > parent)).then_some((idx, indent))
+        })?;
+        parent_indent = Some(indent);
+        found = Some(idx + 1);
+    }
+    found
+}
+
+impl Settings {
+    /// Invalid values of the settings, as `(key, message)` pairs
+    fn validate(&self) -> Vec<(String, String)> {
+        let mut errors = vec![];
+
+        let themes_dir = self.themes_dir().unwrap_or(DEFAULT_THEMES_DIR);
+        if !Path::new(themes_dir).is_absolute() {
+            errors.push((
+                "themes_dir".to_string(),
+                format!("'{themes_dir}' is not an absolute pathname"),
+            ));
+        } else if !Path::new(themes_dir).is_dir() {
+            errors.push((
+                "themes_dir".to_string(),
+                format!("'{themes_dir}' is not a directory"),
+            ));
+        }
+
+        let theme = self.theme();
+        let theme_path = Path::new(theme);
+        let theme_exists = if theme_path.is_absolute() {
+            theme_path.exists()
+        } else {
+            Path::new(themes_dir).join(theme_path).is_dir()
+        };
+        if !theme_exists {
+            errors.push((
+                "greeter.theme".to_string(),
+                format!("theme '{theme}' does not exist"),
+            ));
+        } else if let Some(required) = self.unsupported_api_version() {
+            errors.push((
+                "greeter.theme".to_string(),
+                format!(
+                    "theme '{theme}' requires version {required} of the theme API, \
+                     this greeter provides {}",
+                    protocol::API_VERSION
+                ),
+            ));
+        }
+
+        let dirs = [(
+            "branding.background_images_dir",
+            self.branding_background_images_dir(),
+        )];
+        for (key, dir) in dirs {
+            if !dir.is_empty() && !Path::new(dir).is_dir() {
+                errors.push((key.to_string(), format!("'{dir}' is not a directory")));
+            }
+        }
+
+        let files = [
+            ("branding.logo_image", self.branding_logo_image()),
+            ("branding.user_image", self.branding_user_image()),
+            ("banner.file", self.banner_file()),
+        ];
+        for (key, file) in files {
+            if !file.is_empty() && !Path::new(file).is_file() {
+                errors.push((key.to_string(), format!("'{file}' is not a file")));
+            }
+        }
+
+        let layouts: Vec<String> = lightdm::functions::layouts()
+            .iter()
+            .filter_map(|layout| layout.name().map(|name| name.to_string()))
+            .collect();
+        for layout in self.config_layouts() {
+            if !layouts
+                .iter()
+                .any(|name| *name == layout.replace(" ", "\t"))
+            {
+                errors.push(("layouts".to_string(), format!("unknown layout '{layout}'")));
+            }
+        }
+
+        for action in self.keybindings.profile(false).keys() {
+            if action.debug_only() {
+                errors.push((
+                    "keybindings.normal".to_string(),
+                    format!(
+                        "'{}' is only available in debug mode",
+                        action.detailed_name()
+                    ),
+                ));
+            }
+        }
+
+        errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An empty directory for the config files of the test `name`
+    fn config_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("config-check-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("web-greeter.d")).unwrap();
+        dir
+    }
+
+    #[test]
+    fn missing_config_is_reported() {
+        let path = config_dir("missing").join("web-greeter.yml");
+        let diagnostics = check_configuration(path.to_str());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].file, path);
+        assert_eq!(diagnostics[0].line, None);
+        assert!(diagnostics[0].message.starts_with("can not be read"));
+    }
+
+    #[test]
+    fn unreadable_dropin_is_reported() {
+        let dir = config_dir("dropin");
+        let path = dir.join("web-greeter.yml");
+        std::fs::write(&path, "").unwrap();
+        let dropin = dir.join("web-greeter.d/10-theme.yml");
+        std::fs::write(&dropin, b"greeter:\n  theme: \xff\n").unwrap();
+
+        let diagnostics = check_configuration(path.to_str());
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].file, dropin);
+        assert!(diagnostics[0].message.starts_with("can not be read"));
+    }
+}
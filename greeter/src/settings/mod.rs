@@ -0,0 +1,634 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+mod check;
+
+pub use check::check_configuration;
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_yaml_ng::{Mapping, Value};
+
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    keybindings::{Keybindings, Profile},
+    theme::{DEFAULT_THEMES_DIR, load_theme_html, load_theme_manifest},
+};
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Branding {
+    #[serde(deserialize_with = "nullable_string")]
+    background_images_dir: String,
+    #[serde(deserialize_with = "nullable_string")]
+    logo_image: String,
+    #[serde(deserialize_with = "nullable_string")]
+    user_image: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Greeter {
+    debug_mode: bool,
+    detect_theme_errors: bool,
+    screensaver_timeout: u32,
+    session_fade_out: u32,
+    #[serde(deserialize_with = "nullable_string")]
+    faillock_dir: String,
+    secure_mode: bool,
+    #[serde(deserialize_with = "nullable_string")]
+    theme: String,
+    #[serde(deserialize_with = "nullable_string")]
+    icon_theme: String,
+    #[serde(deserialize_with = "nullable_string")]
+    time_language: String,
+    #[serde(deserialize_with = "nullable_string")]
+    time_format: String,
+}
+
+impl Default for Greeter {
+    fn default() -> Self {
+        Self {
+            debug_mode: false,
+            detect_theme_errors: true,
+            screensaver_timeout: 300,
+            session_fade_out: 0,
+            faillock_dir: "/var/run/faillock".to_string(),
+            secure_mode: true,
+            theme: "gruvbox".to_string(),
+            icon_theme: Default::default(),
+            time_language: Default::default(),
+            time_format: Default::default(),
+        }
+    }
+}
+
+/// Empty values such as `icon_theme:` are read as an empty string
+fn nullable_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(Option::<String>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Banner {
+    #[serde(deserialize_with = "nullable_string")]
+    file: String,
+    acknowledgement_required: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Backlight {
+    enabled: bool,
+    steps: u32,
+    value: i32,
+}
+
+impl Default for Backlight {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            steps: 0,
+            value: 10,
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Features {
+    battery: bool,
+    backlight: Backlight,
+    fingerprint: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Policy {
+    Allow,
+    Deny,
+    DebugOnly,
+}
+
+impl Policy {
+    pub fn allows(&self, debug: bool) -> bool {
+        match self {
+            Policy::Allow => true,
+            Policy::Deny => false,
+            Policy::DebugOnly => debug,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Permissions {
+    pub camera: Policy,
+    pub microphone: Policy,
+    pub notifications: Policy,
+    pub geolocation: Policy,
+}
+
+impl Default for Permissions {
+    fn default() -> Self {
+        Self {
+            camera: Policy::Deny,
+            microphone: Policy::Deny,
+            notifications: Policy::Deny,
+            geolocation: Policy::Deny,
+        }
+    }
+}
+
+/// Order of `lightdm.users`, after the pinned users
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserOrder {
+    /// As listed by LightDM
+    #[default]
+    Lightdm,
+    /// By display name
+    Alphabetical,
+    /// Most recent login first
+    Recent,
+}
+
+/// Inclusive range of UIDs
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct UidRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+impl UidRange {
+    pub fn contains(&self, uid: u32) -> bool {
+        (self.min..=self.max).contains(&uid)
+    }
+}
+
+/// The users listed to themes. Empty `include`, `uid_ranges` and `groups` lists do not filter.
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Users {
+    pub order: UserOrder,
+    /// Usernames listed first, in this order
+    pub pinned: Vec<String>,
+    /// Only list these usernames
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    /// Only list users with a UID in one of these ranges
+    pub uid_ranges: Vec<UidRange>,
+    /// Only list members of one of these groups
+    pub groups: Vec<String>,
+    /// Hide users with one of these login shells
+    pub hidden_shells: Vec<String>,
+}
+
+/// The sessions listed to themes
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sessions {
+    /// Session keys listed first, in this order
+    pub order: Vec<String>,
+    /// Session keys never listed
+    pub hidden: Vec<String>,
+    /// Session types never listed, i.e. `x` or `wayland`
+    pub hidden_types: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    pub primary_html: String,
+    pub secondary_html: Option<String>,
+    pub legacy_api: bool,
+    pub min_api_version: Option<String>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            primary_html: "index.html".to_string(),
+            secondary_html: Default::default(),
+            legacy_api: false,
+            min_api_version: None,
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    branding: Branding,
+    greeter: Greeter,
+    banner: Banner,
+    features: Features,
+    theme: Option<Theme>,
+    themes_dir: Option<String>,
+    layouts: Vec<String>,
+    users: Users,
+    sessions: Sessions,
+    keybindings: Keybindings,
+    permissions: Permissions,
+    #[serde(skip)]
+    source: Source,
+}
+
+/// Arguments the settings were loaded with, so that they can be loaded again
+#[derive(Clone, Default, Debug)]
+struct Source {
+    config: Option<String>,
+    debug: bool,
+    theme: Option<String>,
+}
+
+impl Settings {
+    pub fn new(config: Option<&str>, debug: bool, theme: Option<&str>) -> Self {
+        load_configuration(config, debug, theme)
+    }
+
+    pub fn debug_mode(&self) -> bool {
+        self.greeter.debug_mode
+    }
+
+    pub fn detect_theme_errors(&self) -> bool {
+        self.greeter.detect_theme_errors
+    }
+
+    pub fn screensaver_timeout(&self) -> u32 {
+        self.greeter.screensaver_timeout
+    }
+
+    /// Duration of the fade out before starting a session, in milliseconds
+    pub fn session_fade_out(&self) -> u32 {
+        self.greeter.session_fade_out
+    }
+
+    /// Directory of the pam_faillock tally files, empty to only count the failures seen by the greeter
+    pub fn faillock_dir(&self) -> &str {
+        &self.greeter.faillock_dir
+    }
+
+    pub fn secure_mode(&self) -> bool {
+        self.greeter.secure_mode
+    }
+
+    pub fn theme(&self) -> &str {
+        &self.greeter.theme
+    }
+
+    pub fn icon_theme(&self) -> &str {
+        &self.greeter.icon_theme
+    }
+
+    pub fn time_language(&self) -> &str {
+        &self.greeter.time_language
+    }
+
+    pub fn time_format(&self) -> &str {
+        &self.greeter.time_format
+    }
+
+    pub fn branding_background_images_dir(&self) -> &str {
+        &self.branding.background_images_dir
+    }
+
+    pub fn branding_logo_image(&self) -> &str {
+        &self.branding.logo_image
+    }
+
+    pub fn branding_user_image(&self) -> &str {
+        &self.branding.user_image
+    }
+
+    pub fn banner_file(&self) -> &str {
+        &self.banner.file
+    }
+
+    /// Whether authenticating waits for the banner to be acknowledged, only if there is one
+    pub fn banner_acknowledgement_required(&self) -> bool {
+        self.banner.acknowledgement_required && !self.banner.file.is_empty()
+    }
+
+    pub fn battery(&self) -> bool {
+        self.features.battery
+    }
+
+    pub fn backlight_enabled(&self) -> bool {
+        self.features.backlight.enabled
+    }
+
+    pub fn backlight_value(&self) -> i32 {
+        self.features.backlight.value
+    }
+
+    pub fn backlight_steps(&self) -> u32 {
+        self.features.backlight.steps
+    }
+
+    pub fn fingerprint(&self) -> bool {
+        self.features.fingerprint
+    }
+
+    pub fn branding_section(&self) -> &Branding {
+        &self.branding
+    }
+
+    pub fn greeter_section(&self) -> &Greeter {
+        &self.greeter
+    }
+
+    pub fn features_section(&self) -> &Features {
+        &self.features
+    }
+
+    pub fn config_layouts(&self) -> &Vec<String> {
+        &self.layouts
+    }
+
+    pub fn users(&self) -> &Users {
+        &self.users
+    }
+
+    pub fn sessions(&self) -> &Sessions {
+        &self.sessions
+    }
+
+    pub fn keybindings(&self) -> &Profile {
+        self.keybindings.profile(self.debug_mode())
+    }
+
+    pub fn permissions(&self) -> &Permissions {
+        &self.permissions
+    }
+
+    pub fn primary_html(&self) -> String {
+        self.theme
+            .as_ref()
+            .map_or("index.html".to_string(), |h| h.primary_html.clone())
+    }
+
+    pub fn secondary_html(&self) -> Option<String> {
+        self.theme
+            .as_ref()
+            .map_or(Some(self.primary_html()), |h| h.secondary_html.clone())
+    }
+
+    pub fn legacy_api(&self) -> bool {
+        self.theme.as_ref().is_some_and(|h| h.legacy_api)
+    }
+
+    pub fn min_api_version(&self) -> Option<&str> {
+        self.theme
+            .as_ref()
+            .and_then(|h| h.min_api_version.as_deref())
+    }
+
+    /// The theme API version required by the theme, if this greeter does not provide it
+    pub fn unsupported_api_version(&self) -> Option<&str> {
+        self.min_api_version()
+            .filter(|required| protocol::api_compatible(required) != Some(true))
+    }
+
+    pub fn themes_dir(&self) -> Option<&str> {
+        self.themes_dir.as_deref()
+    }
+
+    /// Load the settings again from the same sources
+    pub fn reload(&self) -> Self {
+        let Source {
+            config,
+            debug,
+            theme,
+        } = &self.source;
+        load_configuration(config.as_deref(), *debug, theme.as_deref())
+    }
+
+    /// The main config file and its drop-in directory
+    pub fn config_files(&self) -> Vec<PathBuf> {
+        let path_to_config =
+            Path::new(self.source.config.as_deref().unwrap_or(DEFAULT_CONFIG_PATH));
+        vec![
+            path_to_config.to_path_buf(),
+            path_to_config.with_extension("d"),
+        ]
+    }
+
+    /// Directory of the active theme
+    pub fn theme_dir(&self) -> Option<PathBuf> {
+        Path::new(&self.primary_html())
+            .parent()
+            .map(|dir| dir.to_path_buf())
+    }
+
+    pub fn change_theme(&mut self, theme: &str) {
+        self.set_theme(theme);
+        self.set_theme_html();
+    }
+
+    fn set_themes_dir(&mut self, themes_dir: &str) {
+        self.themes_dir = Some(themes_dir.to_string())
+    }
+
+    fn set_debug_mode(&mut self, debug_mode: bool) {
+        self.greeter.debug_mode |= debug_mode;
+    }
+
+    fn set_theme(&mut self, theme: &str) {
+        self.greeter.theme = theme.to_string();
+    }
+
+    fn set_theme_html(&mut self) {
+        let theme = self.theme();
+        let themes_dir = self.themes_dir().unwrap_or(DEFAULT_THEMES_DIR);
+        let (primary_html, secondary) = load_theme_html(theme, themes_dir);
+        let manifest = Path::new(&primary_html)
+            .parent()
+            .map(load_theme_manifest)
+            .unwrap_or_default();
+        self.theme = Some(Theme {
+            primary_html,
+            secondary_html: Some(secondary),
+            legacy_api: manifest.legacy_api,
+            min_api_version: manifest.min_api_version,
+        })
+    }
+}
+
+pub const DEFAULT_CONFIG_PATH: &str = "/etc/lightdm/web-greeter.yml";
+const ENV_PREFIX: &str = "WEB_GREETER_";
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Read(PathBuf, io::Error),
+    Parse(PathBuf, serde_yaml_ng::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Read(path, e) => write!(f, "Can not read {}: {e}", path.display()),
+            ConfigError::Parse(path, e) => write!(f, "Invalid config {}: {e}", path.display()),
+        }
+    }
+}
+
+pub fn load_configuration(config: Option<&str>, debug: bool, theme: Option<&str>) -> Settings {
+    let source = Source {
+        config: config.map(|s| s.to_string()),
+        debug,
+        theme: theme.map(|s| s.to_string()),
+    };
+    let path_to_config = Path::new(config.unwrap_or(DEFAULT_CONFIG_PATH));
+    let mut config = match read_configuration(path_to_config) {
+        Ok(config) => config,
+        Err(e) => {
+            logger_error!("{e}\n\tFalling back to built-in defaults");
+            Settings::default()
+        }
+    };
+    config.source = source;
+    if debug {
+        config.set_debug_mode(true);
+    }
+    if let Some(theme) = theme {
+        config.set_theme(theme);
+    }
+    if let Some(themes_dir) = config.themes_dir()
+        && !Path::new(themes_dir).is_absolute()
+    {
+        logger_error!("themes_dir '{themes_dir}' is not an absolute pathname");
+        config.themes_dir = None;
+    }
+    if config.themes_dir().is_none() {
+        config.set_themes_dir(DEFAULT_THEMES_DIR);
+    }
+    config.set_theme_html();
+
+    logger_debug!("Configuration loaded");
+    config
+}
+
+/// Effective settings after defaults, CLI flags and theme resolution, as YAML
+pub fn dump_configuration(config: Option<&str>, debug: bool, theme: Option<&str>) -> String {
+    let settings = load_configuration(config, debug, theme);
+    serde_yaml_ng::to_string(&settings).expect("Settings is serializable")
+}
+
+/// The main config file followed by the `*.yml` drop-ins of its `.d` directory, in lexical order
+pub fn config_sources(path_to_config: &Path) -> Vec<PathBuf> {
+    let mut dropins: Vec<PathBuf> = match std::fs::read_dir(path_to_config.with_extension("d")) {
+        Ok(dir) => dir
+            .filter_map(|ent| ent.ok())
+            .map(|ent| ent.path())
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "yml"))
+            .collect(),
+        Err(_) => vec![],
+    };
+    dropins.sort();
+
+    let mut sources = vec![path_to_config.to_path_buf()];
+    sources.extend(dropins);
+    sources
+}
+
+/// The main config file must be valid, a drop-in or an environment variable which can not be
+/// read, parsed or merged into valid settings is skipped so that the other sources still apply
+fn read_configuration(path_to_config: &Path) -> Result<Settings, ConfigError> {
+    let mut merged = Value::Mapping(Mapping::new());
+    for source in config_sources(path_to_config) {
+        let layer = match read_layer(&source) {
+            Ok(layer) => layer,
+            Err(ConfigError::Read(_, e))
+                if source == path_to_config && e.kind() == io::ErrorKind::NotFound =>
+            {
+                logger_warn!("{} does not exist", source.display());
+                continue;
+            }
+            Err(e) if source == path_to_config => return Err(e),
+            Err(e) => {
+                logger_error!("{e}\n\tSkipping this drop-in");
+                continue;
+            }
+        };
+        if layer.is_null() {
+            continue;
+        }
+        match merge_valid(&mut merged, layer) {
+            Ok(()) => {}
+            Err(e) if source == path_to_config => return Err(ConfigError::Parse(source, e)),
+            Err(e) => logger_error!(
+                "{}\n\tSkipping this drop-in",
+                ConfigError::Parse(source.clone(), e)
+            ),
+        }
+    }
+    for (key, layer) in env_overrides() {
+        if let Err(e) = merge_valid(&mut merged, layer) {
+            logger_error!("Invalid environment variable {key}: {e}\n\tSkipping this override");
+        }
+    }
+
+    serde_yaml_ng::from_value(merged)
+        .map_err(|e| ConfigError::Parse(path_to_config.to_path_buf(), e))
+}
+
+/// Merge `layer` into `merged` if they make valid settings
+fn merge_valid(merged: &mut Value, layer: Value) -> Result<(), serde_yaml_ng::Error> {
+    let mut candidate = merged.clone();
+    merge(&mut candidate, layer);
+    serde_yaml_ng::from_value::<Settings>(candidate.clone())?;
+    *merged = candidate;
+    Ok(())
+}
+
+fn read_layer(source: &Path) -> Result<Value, ConfigError> {
+    let content =
+        std::fs::read_to_string(source).map_err(|e| ConfigError::Read(source.to_path_buf(), e))?;
+    serde_yaml_ng::from_str::<Value>(&content)
+        .map_err(|e| ConfigError::Parse(source.to_path_buf(), e))
+}
+
+fn merge(base: &mut Value, layer: Value) {
+    match (base, layer) {
+        (Value::Mapping(base), Value::Mapping(layer)) => {
+            for (key, value) in layer {
+                match base.get_mut(&key) {
+                    Some(base_value) => merge(base_value, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, layer) => *base = layer,
+    }
+}
+
+/// `WEB_GREETER_GREETER__THEME=litarvan` overrides `greeter.theme`.
+/// Values are parsed as YAML, so `true`, `300` or `[us, latam]` keep their types.
+/// Each override is a layer of its own, along with the variable it comes from
+fn env_overrides() -> Vec<(String, Value)> {
+    let mut overrides = vec![];
+    for (key, value) in std::env::vars() {
+        let Some(path) = key.strip_prefix(ENV_PREFIX) else {
+            continue;
+        };
+        let value = serde_yaml_ng::from_str::<Value>(&value).unwrap_or(Value::String(value));
+        let layer = path
+            .to_lowercase()
+            .rsplit("__")
+            .fold(value, |value, segment| {
+                let mut mapping = Mapping::new();
+                mapping.insert(Value::String(segment.to_string()), value);
+                Value::Mapping(mapping)
+            });
+        logger_debug!("Config overridden by environment variable {key}");
+        overrides.push((key, layer));
+    }
+    overrides
+}
@@ -2,11 +2,25 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use std::{collections::BTreeMap, path::PathBuf};
+use serde::Deserialize;
+
+use std::path::{Path, PathBuf};
 
 pub const DEFAULT_THEMES_DIR: &str = "/usr/share/web-greeter/themes/";
 const DEFAULT_THEME: &str = "default";
 
+/// Theme manifest, read from the `index.yml` of the theme directory
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct ThemeManifest {
+    pub primary_html: Option<String>,
+    pub secondary_html: Option<String>,
+    /// Expose the lightdm-webkit2-greeter API to the theme
+    pub legacy_api: bool,
+    /// Oldest version of the theme API the theme works with
+    pub min_api_version: Option<String>,
+}
+
 fn list_themes() -> Vec<String> {
     let mut themes = match std::fs::read_dir(DEFAULT_THEMES_DIR) {
         Ok(dir) => dir
@@ -68,7 +82,11 @@ pub fn load_theme_html(theme: &str, themes_dir: &str) -> (String, String) {
         themes_dir_path.join(DEFAULT_THEME)
     };
 
-    let (primary, secondary) = load_theme_config(&theme_dir);
+    let manifest = load_theme_manifest(&theme_dir);
+    let primary = manifest
+        .primary_html
+        .unwrap_or_else(|| "index.html".to_string());
+    let secondary = manifest.secondary_html;
     let primary_html = if absolute_path.is_file() && theme.ends_with(".html") {
         absolute_path.to_string_lossy().to_string()
     } else {
@@ -93,29 +111,18 @@ pub fn load_theme_html(theme: &str, themes_dir: &str) -> (String, String) {
     }
 }
 
-fn load_theme_config(theme_dir: &PathBuf) -> (String, Option<String>) {
-    match std::fs::read_to_string(PathBuf::from(&theme_dir).join("index.yml")) {
-        Ok(content) => match serde_yaml_ng::from_str::<BTreeMap<String, String>>(&content) {
-            Ok(config_map) => {
-                let primary = config_map
-                    .get("primary_html")
-                    .unwrap_or(&"index.html".to_string())
-                    .clone();
-                let secondary = config_map.get("secondary_html");
-                if let Some(s) = secondary {
-                    (primary, Some(s.clone()))
-                } else {
-                    (primary, None)
-                }
-            }
+pub fn load_theme_manifest(theme_dir: &Path) -> ThemeManifest {
+    match std::fs::read_to_string(theme_dir.join("index.yml")) {
+        Ok(content) => match serde_yaml_ng::from_str::<ThemeManifest>(&content) {
+            Ok(manifest) => manifest,
             Err(e) => {
                 logger_error!("Parsing failed: \n\t{e}");
-                ("index.html".to_string(), None)
+                Default::default()
             }
         },
         Err(e) => {
             logger_error!("Theme config was not loaded:\n\t{e}");
-            ("index.html".to_string(), None)
+            Default::default()
         }
     }
 }
@@ -0,0 +1,95 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use gtk::{
+    gio::{self, Cancellable, FileMonitor, FileMonitorEvent, FileMonitorFlags, prelude::*},
+    glib::{self, SourceId},
+};
+
+use std::{
+    cell::RefCell,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::Duration,
+};
+
+/// Editors usually emit several events per save, only the last one within this delay is reported
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(250);
+
+/// `root` and its subdirectories, since a directory monitor does not report changes in them.
+/// Hidden directories and symbolic links are skipped
+pub fn directory_tree(root: &Path) -> Vec<PathBuf> {
+    let mut dirs = vec![root.to_path_buf()];
+    let mut index = 0;
+    while let Some(dir) = dirs.get(index).cloned() {
+        index += 1;
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut children: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| {
+                entry.file_type().is_ok_and(|ty| ty.is_dir())
+                    && !entry.file_name().to_string_lossy().starts_with('.')
+            })
+            .map(|entry| entry.path())
+            .collect();
+        children.sort();
+        dirs.extend(children);
+    }
+    dirs
+}
+
+/// Monitor `paths`, files or directories, and call `callback` with the last changed path.
+/// The returned monitors stop watching once dropped
+pub fn watch<F>(paths: &[PathBuf], callback: F) -> Vec<FileMonitor>
+where
+    F: Fn(&Path) + 'static,
+{
+    let callback = Rc::new(callback);
+    paths
+        .iter()
+        .filter_map(|path| {
+            let monitor = match gio::File::for_path(path)
+                .monitor(FileMonitorFlags::WATCH_MOVES, Cancellable::NONE)
+            {
+                Ok(monitor) => monitor,
+                Err(e) => {
+                    logger_warn!("Can not watch {}: {}", path.display(), e.message());
+                    return None;
+                }
+            };
+
+            let pending: Rc<RefCell<Option<SourceId>>> = Default::default();
+            let callback = callback.clone();
+            monitor.connect_changed(move |_, file, other, event| {
+                let file = match event {
+                    FileMonitorEvent::ChangesDoneHint
+                    | FileMonitorEvent::Created
+                    | FileMonitorEvent::Deleted
+                    | FileMonitorEvent::MovedIn
+                    | FileMonitorEvent::MovedOut => file,
+                    FileMonitorEvent::Renamed => other.unwrap_or(file),
+                    _ => return,
+                };
+                let Some(path) = file.path() else {
+                    return;
+                };
+
+                if let Some(source) = pending.take() {
+                    source.remove();
+                }
+                let callback = callback.clone();
+                let pending_source = pending.clone();
+                let source = glib::timeout_add_local_once(DEBOUNCE_DELAY, move || {
+                    pending_source.take();
+                    callback(&path);
+                });
+                pending.replace(Some(source));
+            });
+            logger_debug!("Watching {}", path.display());
+            Some(monitor)
+        })
+        .collect()
+}
@@ -3,13 +3,20 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use gtk::{gdk, gio::Cancellable};
-use webkit::{HardwareAccelerationPolicy, Settings, UserMessage, WebView, prelude::*};
+use webkit::{
+    GeolocationPermissionRequest, HardwareAccelerationPolicy, NotificationPermissionRequest,
+    PermissionRequest, Settings, UserMediaPermissionRequest, UserMessage, WebView, prelude::*,
+};
 
 use std::{cell::Cell, rc::Rc};
 
-use crate::{bridge::Dispatcher, browser::BrowserProperties};
+use crate::{
+    bridge::Dispatcher,
+    browser::BrowserProperties,
+    settings::{Permissions, Policy},
+};
 
-pub fn webview_new(debug: bool, theme_file: &str) -> WebView {
+pub fn webview_new(debug: bool, permissions: &Permissions, theme_file: &str) -> WebView {
     let settings = Settings::builder()
         .allow_file_access_from_file_urls(true)
         .allow_universal_access_from_file_urls(true)
@@ -25,6 +32,11 @@ pub fn webview_new(debug: bool, theme_file: &str) -> WebView {
     let rgba = gdk::RGBA::parse("#000000").unwrap();
     webview.set_background_color(&rgba);
 
+    let permissions = permissions.clone();
+    webview.connect_permission_request(move |_, request| {
+        permission_request(request, &permissions, debug)
+    });
+
     let uri = "file://".to_string() + theme_file;
     webview.load_uri(&uri);
     logger_debug!("Theme loaded");
@@ -32,6 +44,41 @@ pub fn webview_new(debug: bool, theme_file: &str) -> WebView {
     webview
 }
 
+fn permission_request(request: &PermissionRequest, permissions: &Permissions, debug: bool) -> bool {
+    let policies: Vec<(&str, Policy)> =
+        if let Some(media) = request.downcast_ref::<UserMediaPermissionRequest>() {
+            let mut policies = vec![];
+            if media.is_for_video_device() {
+                policies.push(("camera", permissions.camera));
+            }
+            if media.is_for_audio_device() {
+                policies.push(("microphone", permissions.microphone));
+            }
+            policies
+        } else if request.is::<NotificationPermissionRequest>() {
+            vec![("notifications", permissions.notifications)]
+        } else if request.is::<GeolocationPermissionRequest>() {
+            vec![("geolocation", permissions.geolocation)]
+        } else {
+            // Let WebKit apply its default behavior to other requests
+            return false;
+        };
+
+    let kinds = policies
+        .iter()
+        .map(|(kind, _)| *kind)
+        .collect::<Vec<_>>()
+        .join(", ");
+    if !policies.is_empty() && policies.iter().all(|(_, p)| p.allows(debug)) {
+        logger_debug!("Permission request allowed: {kinds}");
+        request.allow();
+    } else {
+        logger_warn!("Permission request denied: {kinds}");
+        request.deny();
+    }
+    true
+}
+
 pub fn user_message_received(
     webview: &WebView,
     message: &UserMessage,
@@ -54,6 +101,12 @@ pub fn user_message_received(
 
             loaded.set(true);
             logger_debug!("Lightdm webkit greeter started win: {}", window.id());
+
+            if win_props.is_primary
+                && let Some(required) = dispatcher.unsupported_api_version()
+            {
+                show_api_version_prompt(webview, &required, dispatcher);
+            }
             true
         }
         Some("console") => {
@@ -68,6 +121,42 @@ pub fn user_message_received(
     }
 }
 
+/// Offer to fall back to the default theme when the theme requires a newer theme API
+fn show_api_version_prompt(webview: &WebView, required: &str, dispatcher: &Rc<Dispatcher>) {
+    let detail = format!(
+        "The theme requires version {required} of the theme API, this greeter provides {}.",
+        protocol::API_VERSION
+    );
+    logger_warn!("{detail}");
+
+    let dialog = gtk::AlertDialog::builder()
+        .message(
+            "The theme requires a newer greeter. Do you want to change to default theme? (gruvbox)",
+        )
+        .detail(detail)
+        .buttons(["_Cancel", "_Use default theme"])
+        .build();
+
+    let root = webview.root().expect("webview.root is None");
+    let window = root
+        .downcast_ref::<gtk::ApplicationWindow>()
+        .expect("webview.root is not a ApplicationWindow");
+
+    dialog.choose(
+        Some(window),
+        Some(&Cancellable::new()),
+        gtk::glib::clone!(
+            #[strong]
+            dispatcher,
+            move |res| {
+                if let Ok(1) = res {
+                    dispatcher.change_theme(Some("gruvbox"));
+                }
+            }
+        ),
+    );
+}
+
 pub fn show_console_error_prompt(
     webview: &WebView,
     message: &UserMessage,
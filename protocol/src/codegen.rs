@@ -0,0 +1,223 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use std::fmt::Write;
+
+use crate::{
+    definition::{INTERFACES, OBJECTS},
+    schema::{Arg, Interface, Kind, Object, Type},
+    version::API_VERSION,
+};
+
+const MAIN_FRAME_ONLY: &str = "Only available to the top-level frame of the theme.";
+
+const TYPESCRIPT_PRELUDE: &str = "\
+declare class LightDMSignal<Args extends unknown[] = []> {
+  connect(callback: (...args: Args) => void): void;
+  disconnect(callback: (...args: Args) => void): void;
+}
+
+interface GreeterBroadcastEvent extends Event {
+  window: null;
+  data: any;
+}
+";
+
+fn header() -> String {
+    format!(
+        "// Generated from the bridge protocol definition of theme API {API_VERSION}, do not edit.\n"
+    )
+}
+
+/// Data classes returned by the theme API, the objects themselves are native classes
+/// registered by the web process extension.
+pub fn javascript() -> String {
+    let mut out = header();
+    for interface in INTERFACES.iter().filter(|interface| interface.class) {
+        js_class(&mut out, interface);
+    }
+    out
+}
+
+/// TypeScript declarations of the theme API
+pub fn typescript() -> String {
+    let mut out = header();
+    out.push('\n');
+    out.push_str(TYPESCRIPT_PRELUDE);
+    for interface in INTERFACES {
+        ts_interface(&mut out, interface);
+    }
+    for object in OBJECTS {
+        ts_object(&mut out, object);
+    }
+    ts_window_events(&mut out);
+    out.push('\n');
+    for object in OBJECTS {
+        writeln!(out, "declare var {}: {};", object.name, object.class).unwrap();
+    }
+    out
+}
+
+fn js_class(out: &mut String, interface: &Interface) {
+    let fields: Vec<&str> = interface.fields.iter().map(|field| field.name).collect();
+    writeln!(out, "\n/**\n * {}\n */", interface.doc).unwrap();
+    writeln!(out, "class {} {{", interface.name).unwrap();
+    for field in &fields {
+        writeln!(out, "  {field};").unwrap();
+    }
+    writeln!(out, "  constructor({{ {} }}) {{", fields.join(", ")).unwrap();
+    for field in &fields {
+        writeln!(out, "    this.{field} = {field};").unwrap();
+    }
+    out.push_str("  }\n}\n");
+}
+
+fn ts_interface(out: &mut String, interface: &Interface) {
+    writeln!(out, "\n/** {} */", interface.doc).unwrap();
+    if interface.class {
+        writeln!(out, "declare class {} {{", interface.name).unwrap();
+    } else {
+        writeln!(out, "interface {} {{", interface.name).unwrap();
+    }
+    for field in interface.fields {
+        writeln!(out, "  readonly {}: {};", field.name, field.ty.notation()).unwrap();
+    }
+    out.push_str("}\n");
+}
+
+fn ts_object(out: &mut String, object: &Object) {
+    writeln!(out, "\n/** {} */", object.doc).unwrap();
+    writeln!(out, "declare class {} {{", object.class).unwrap();
+    for event in object.events {
+        let args = ts_args(event.args);
+        let mut doc = doc_lines(event.doc);
+        if event.main_frame_only {
+            doc.push(MAIN_FRAME_ONLY.to_string());
+        }
+        ts_doc(out, &doc);
+        writeln!(
+            out,
+            "  readonly {}: LightDMSignal<[{}]>;",
+            event.name,
+            args.join(", ")
+        )
+        .unwrap();
+    }
+
+    for call in object.calls {
+        match call.kind {
+            Kind::Get => {
+                let readonly = if object.call(call.name, Kind::Set).is_some() {
+                    ""
+                } else {
+                    "readonly "
+                };
+                let mut doc = doc_lines(call.doc);
+                if let Some(events) = call.cached_until {
+                    let events: Vec<String> =
+                        events.iter().map(|event| format!("`{event}`")).collect();
+                    doc.push(format!(
+                        "Cached by the theme API until {} is emitted.",
+                        events.join(" or ")
+                    ));
+                }
+                ts_doc(out, &doc);
+                writeln!(out, "  {readonly}{}: {};", call.name, call.ret.notation()).unwrap();
+            }
+            Kind::Set => {}
+            Kind::Call => {
+                // Local methods wrap the call of the same name
+                if object.locals.iter().any(|local| local.name == call.name) {
+                    continue;
+                }
+                let doc = if call.main_frame_only {
+                    format!("{}\n{MAIN_FRAME_ONLY}", call.doc)
+                } else {
+                    call.doc.to_string()
+                };
+                ts_method(out, call.name, call.args, &call.ret, &doc);
+            }
+        }
+    }
+    for local in object.locals {
+        ts_method(out, local.name, local.args, &local.ret, local.doc);
+    }
+    out.push_str("}\n");
+}
+
+/// Events are dispatched on the window as well, as `CustomEvent`s named `<object>:<event>`
+/// with the arguments as `detail`
+fn ts_window_events(out: &mut String) {
+    out.push_str("\ninterface WindowEventMap {\n");
+    out.push_str("  GreeterBroadcastEvent: GreeterBroadcastEvent;\n");
+    out.push_str("  GreeterReady: Event;\n");
+    for object in OBJECTS {
+        for event in object.events {
+            writeln!(
+                out,
+                "  \"{}:{}\": CustomEvent<[{}]>;",
+                object.name,
+                event.name,
+                ts_args(event.args).join(", ")
+            )
+            .unwrap();
+        }
+    }
+    out.push_str("}\n");
+}
+
+fn ts_args(args: &[Arg]) -> Vec<String> {
+    args.iter()
+        .map(|arg| format!("{}: {}", arg.name, arg.ty.notation()))
+        .collect()
+}
+
+fn ts_method(out: &mut String, name: &str, args: &[Arg], ret: &Type, doc: &str) {
+    let mut lines = doc_lines(doc);
+    lines.extend(
+        args.iter()
+            .filter(|arg| !arg.doc.is_empty())
+            .map(|arg| format!("@param {} {}", arg.name, arg.doc)),
+    );
+    ts_doc(out, &lines);
+
+    let optional = optional_from(args);
+    let params: Vec<String> = args
+        .iter()
+        .enumerate()
+        .map(|(index, arg)| {
+            let mark = if index >= optional { "?" } else { "" };
+            format!("{}{mark}: {}", arg.name, arg.ty.notation())
+        })
+        .collect();
+    writeln!(out, "  {name}({}): {};", params.join(", "), ret.notation()).unwrap();
+}
+
+fn ts_doc(out: &mut String, lines: &[String]) {
+    match lines {
+        [] => {}
+        [line] => writeln!(out, "  /** {line} */").unwrap(),
+        lines => {
+            out.push_str("  /**\n");
+            for line in lines {
+                writeln!(out, "   * {line}").unwrap();
+            }
+            out.push_str("   */\n");
+        }
+    }
+}
+
+fn doc_lines(doc: &str) -> Vec<String> {
+    doc.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// Index of the first argument of the trailing nullable ones
+fn optional_from(args: &[Arg]) -> usize {
+    args.iter()
+        .rposition(|arg| !arg.ty.is_nullable())
+        .map_or(0, |index| index + 1)
+}
@@ -0,0 +1,928 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use crate::schema::{Arg, Call, Event, Field, Interface, Kind, Local, Object, Type};
+
+const NULLABLE_STRING: Type = Type::Nullable(&Type::String);
+const NULLABLE_NUMBER: Type = Type::Nullable(&Type::Number);
+
+const fn getter(name: &'static str, ret: Type, doc: &'static str) -> Call {
+    Call {
+        name,
+        kind: Kind::Get,
+        args: &[],
+        ret,
+        doc,
+        main_frame_only: false,
+        cached_until: None,
+    }
+}
+
+const fn setter(name: &'static str, args: &'static [Arg]) -> Call {
+    Call {
+        name,
+        kind: Kind::Set,
+        args,
+        ret: Type::Void,
+        doc: "",
+        main_frame_only: false,
+        cached_until: None,
+    }
+}
+
+const fn method(name: &'static str, args: &'static [Arg], ret: Type, doc: &'static str) -> Call {
+    Call {
+        name,
+        kind: Kind::Call,
+        args,
+        ret,
+        doc,
+        main_frame_only: false,
+        cached_until: None,
+    }
+}
+
+const fn arg(name: &'static str, ty: Type, doc: &'static str) -> Arg {
+    Arg { name, ty, doc }
+}
+
+const fn field(name: &'static str, ty: Type) -> Field {
+    Field { name, ty }
+}
+
+const fn event(name: &'static str, args: &'static [Arg], doc: &'static str) -> Event {
+    Event {
+        name,
+        args,
+        doc,
+        main_frame_only: false,
+    }
+}
+
+pub static OBJECTS: &[Object] = &[
+    Object {
+        name: "lightdm",
+        class: "LightDMGreeter",
+        doc: "Interface to LightDM, available as `window.lightdm`.",
+        calls: &[
+            getter(
+                "auth_state",
+                Type::Object("LightDMAuthState"),
+                "The state of the authentication flow.",
+            ),
+            getter(
+                "authentication_user",
+                NULLABLE_STRING,
+                "The username of the user being authenticated or `null` if there is no authentication in progress.",
+            ),
+            getter(
+                "autologin_guest",
+                Type::Bool,
+                "Whether or not the guest account should be automatically logged into when the timer expires.",
+            ),
+            getter(
+                "autologin_timeout",
+                Type::Number,
+                "The number of seconds to wait before automatically logging in.",
+            ),
+            getter(
+                "autologin_user",
+                NULLABLE_STRING,
+                "The username with which to automatically log in when the timer expires.",
+            ),
+            getter(
+                "battery_data",
+                Type::Nullable(&Type::Class("LightDMBattery")),
+                "The battery data, or `null` if the battery is not accessible.",
+            ),
+            getter(
+                "brightness",
+                Type::Number,
+                "The display brightness in percent, or -1 if the backlight is not accessible.",
+            ),
+            setter(
+                "brightness",
+                &[arg("value", Type::Number, "The brightness in percent.")],
+            ),
+            getter(
+                "can_access_battery",
+                Type::Bool,
+                "Whether or not the greeter can access the battery.",
+            ),
+            getter(
+                "can_access_brightness",
+                Type::Bool,
+                "Whether or not the greeter can control the display brightness.",
+            ),
+            getter(
+                "can_hibernate",
+                Type::Bool,
+                "Whether or not the greeter can make the system hibernate.",
+            ),
+            getter(
+                "can_restart",
+                Type::Bool,
+                "Whether or not the greeter can make the system restart.",
+            ),
+            getter(
+                "can_shutdown",
+                Type::Bool,
+                "Whether or not the greeter can make the system shutdown.",
+            )
+            .cached_until(&["reset"]),
+            getter(
+                "can_suspend",
+                Type::Bool,
+                "Whether or not the greeter can make the system suspend/sleep.",
+            ),
+            getter(
+                "default_session",
+                NULLABLE_STRING,
+                "The name of the default session.",
+            ),
+            getter(
+                "has_guest_account",
+                Type::Bool,
+                "Whether or not guest sessions are supported.",
+            ),
+            getter(
+                "hide_users_hint",
+                Type::Bool,
+                "Whether or not user accounts should be hidden.",
+            ),
+            getter("hostname", NULLABLE_STRING, "The system's hostname."),
+            getter(
+                "in_authentication",
+                Type::Bool,
+                "Whether or not the greeter is in the process of authenticating.",
+            ),
+            getter(
+                "is_authenticated",
+                Type::Bool,
+                "Whether or not the greeter has successfully authenticated.",
+            ),
+            getter(
+                "language",
+                Type::Nullable(&Type::Class("LightDMLanguage")),
+                "The current language or `null` if no language.",
+            ),
+            getter(
+                "languages",
+                Type::Array(&Type::Class("LightDMLanguage")),
+                "A list of languages to present to the user.",
+            )
+            .cached_until(&["reset"]),
+            getter(
+                "last_state",
+                Type::Object("LightDMLastState"),
+                "The last user who logged in and what each user last logged in with, remembered by\n\
+                 the greeter. The `users` fill in the session, language and layout from it.",
+            ),
+            getter(
+                "layout",
+                Type::Nullable(&Type::Class("LightDMLayout")),
+                "The currently active layout for the selected user.",
+            ),
+            setter(
+                "layout",
+                &[arg(
+                    "value",
+                    Type::Object("LightDMLayout"),
+                    "The layout to activate.",
+                )],
+            ),
+            getter(
+                "layouts",
+                Type::Array(&Type::Class("LightDMLayout")),
+                "A list of keyboard layouts to present to the user.",
+            )
+            .cached_until(&["reset"]),
+            getter(
+                "lock_hint",
+                Type::Bool,
+                "Whether or not the greeter was started as a lock screen.",
+            ),
+            getter(
+                "recent_users",
+                Type::Array(&Type::Class("LightDMUser")),
+                "The users with a known last login, the most recent first.",
+            )
+            .cached_until(&["reset", "users_changed", "greeter_config:config_changed"]),
+            getter(
+                "remote_sessions",
+                Type::Array(&Type::Class("LightDMSession")),
+                "The available remote sessions.",
+            ),
+            getter(
+                "select_guest_hint",
+                Type::Bool,
+                "Whether or not the guest account should be selected by default.",
+            ),
+            getter(
+                "select_user_hint",
+                NULLABLE_STRING,
+                "The username to select by default, or the last user who logged in.",
+            ),
+            getter(
+                "sessions",
+                Type::Array(&Type::Class("LightDMSession")),
+                "List of available sessions, filtered and ordered by the `sessions` configuration.",
+            )
+            .cached_until(&["reset", "greeter_config:config_changed"]),
+            getter(
+                "shared_data_directory",
+                NULLABLE_STRING,
+                "LightDM's shared data directory, readable by the greeter and the users.",
+            ),
+            getter(
+                "show_manual_login_hint",
+                Type::Bool,
+                "Whether or not a manual login option should be shown. If `true`, the theme should\n\
+                 provide a way for a username to be entered manually. Otherwise, themes that show\n\
+                 a user list may limit logins to only those users.",
+            ),
+            getter(
+                "show_remote_login_hint",
+                Type::Bool,
+                "Whether or not a remote login option should be shown. If `true`, the theme should\n\
+                 provide a way for a user to log into a remote desktop server.",
+            ),
+            getter(
+                "users",
+                Type::Array(&Type::Class("LightDMUser")),
+                "List of available users, filtered and ordered by the `users` configuration.",
+            )
+            .cached_until(&["reset", "users_changed", "greeter_config:config_changed"]),
+            method(
+                "auth_failures",
+                &[arg("username", Type::String, "The user to check.")],
+                Type::Object("LightDMAuthFailures"),
+                "The failed authentications of `username` and whether pam_faillock locked the account.",
+            ),
+            method(
+                "authenticate",
+                &[arg(
+                    "username",
+                    NULLABLE_STRING,
+                    "A username or `null` to prompt for a username.",
+                )],
+                Type::Bool,
+                "Starts the authentication procedure for a user. Fails while an authentication is\n\
+                 already in progress, which must be cancelled first, or for a user filtered out by\n\
+                 the `users` configuration unless `show_manual_login_hint` is set.",
+            )
+            .main_frame_only(),
+            method(
+                "authenticate_as_guest",
+                &[],
+                Type::Bool,
+                "Starts the authentication procedure for the guest user.",
+            )
+            .main_frame_only(),
+            method(
+                "authenticate_remote",
+                &[
+                    arg(
+                        "session",
+                        Type::String,
+                        "The remote session to authenticate against.",
+                    ),
+                    arg(
+                        "username",
+                        NULLABLE_STRING,
+                        "A username or `null` to prompt for a username.",
+                    ),
+                ],
+                Type::Bool,
+                "Starts the authentication procedure for a remote session, which `start_session`\n\
+                 then starts.",
+            )
+            .main_frame_only(),
+            method(
+                "brightness_decrease",
+                &[arg(
+                    "quantity",
+                    NULLABLE_NUMBER,
+                    "The amount in percent, defaults to `features.backlight.value`.",
+                )],
+                Type::Bool,
+                "Decrease the display brightness.",
+            ),
+            method(
+                "brightness_increase",
+                &[arg(
+                    "quantity",
+                    NULLABLE_NUMBER,
+                    "The amount in percent, defaults to `features.backlight.value`.",
+                )],
+                Type::Bool,
+                "Increase the display brightness.",
+            ),
+            method(
+                "brightness_set",
+                &[arg("quantity", Type::Number, "The brightness in percent.")],
+                Type::Bool,
+                "Set the display brightness.",
+            ),
+            method(
+                "cancel_authentication",
+                &[],
+                Type::Bool,
+                "Cancel the user authentication that is currently in progress.",
+            )
+            .main_frame_only(),
+            method(
+                "cancel_autologin",
+                &[],
+                Type::Bool,
+                "Cancel the automatic login.",
+            ),
+            method(
+                "fingerprint_available",
+                &[arg("username", Type::String, "The user to check.")],
+                Type::Bool,
+                "Whether a fingerprint reader is present and `username` has enrolled prints, as last\n\
+                 checked, always `false` unless `features.fingerprint` is enabled. fprintd is asked\n\
+                 again in the background, `fingerprint_available_changed` reports a new answer.",
+            ),
+            method(
+                "hibernate",
+                &[],
+                Type::Bool,
+                "Triggers the system to hibernate.",
+            ),
+            method(
+                "respond",
+                &[arg("response", Type::String, "The response to the prompt.")],
+                Type::Bool,
+                "Provide a response to a prompt, fails if no prompt is pending.",
+            )
+            .main_frame_only(),
+            method(
+                "restart",
+                &[],
+                Type::Bool,
+                "Triggers the system to restart.",
+            ),
+            method(
+                "set_language",
+                &[arg(
+                    "language",
+                    Type::String,
+                    "The language in the form of a locale specification (e.g. 'de_DE.UTF-8').",
+                )],
+                Type::Bool,
+                "Set the language for the currently authenticated user.",
+            ),
+            method(
+                "shutdown",
+                &[],
+                Type::Bool,
+                "Triggers the system to shutdown.",
+            ),
+            method(
+                "start_session",
+                &[arg(
+                    "session",
+                    NULLABLE_STRING,
+                    "The session to log into or `null` for the default, unused after a remote login.",
+                )],
+                Type::Bool,
+                "Start a session for the authenticated user. Returns once the session is requested,\n\
+                 `session_started` or `session_failed` is emitted when LightDM answers.",
+            )
+            .main_frame_only(),
+            method(
+                "suspend",
+                &[],
+                Type::Bool,
+                "Triggers the system to suspend/sleep.",
+            ),
+        ],
+        events: &[
+            event(
+                "auth_state_changed",
+                &[arg(
+                    "state",
+                    Type::Object("LightDMAuthState"),
+                    "The new state of the authentication flow.",
+                )],
+                "Emitted when the authentication flow moves to another state, or a message is shown.",
+            )
+            .main_frame_only(),
+            event(
+                "authentication_complete",
+                &[],
+                "Emitted when the authentication is complete, check `is_authenticated` for the result.",
+            )
+            .main_frame_only(),
+            event(
+                "autologin_timer_expired",
+                &[],
+                "Emitted when the automatic login timer has expired.",
+            ),
+            event(
+                "battery_update",
+                &[arg(
+                    "data",
+                    Type::Nullable(&Type::Object("LightDMBattery")),
+                    "The new battery data.",
+                )],
+                "Emitted when the battery data changed.",
+            ),
+            event(
+                "brightness_update",
+                &[],
+                "Emitted when the display brightness changed.",
+            ),
+            event(
+                "fingerprint_available_changed",
+                &[
+                    arg("username", Type::String, "The user checked."),
+                    arg("available", Type::Bool, "Whether the user can now log in with a fingerprint."),
+                ],
+                "Emitted when fprintd reports that a user enrolled prints on a reader or no longer has any.",
+            ),
+            event(
+                "fingerprint_status",
+                &[
+                    arg(
+                        "result",
+                        Type::String,
+                        "The fprintd result without its `verify-` prefix, e.g. `match` or `retry-scan`.",
+                    ),
+                    arg("done", Type::Bool, "Whether the verification is over."),
+                ],
+                "Emitted for each scan while PAM verifies a fingerprint, alongside its messages.",
+            )
+            .main_frame_only(),
+            event(
+                "password_change_required",
+                &[],
+                "Emitted when the password expired and PAM asks for a new one, the following\n\
+                 prompts have a `purpose`.",
+            )
+            .main_frame_only(),
+            event(
+                "reset",
+                &[],
+                "Emitted when LightDM resets the greeter, e.g. before reusing it for a new login.",
+            ),
+            event(
+                "session_failed",
+                &[arg("error", Type::String, "The error reported by LightDM.")],
+                "Emitted when the session could not be started.",
+            ),
+            event(
+                "session_started",
+                &[],
+                "Emitted when LightDM started the session, the greeter is about to exit.",
+            ),
+            event(
+                "session_starting",
+                &[arg(
+                    "session",
+                    NULLABLE_STRING,
+                    "The session being started or `null` for the default.",
+                )],
+                "Emitted when `start_session` was called, before the windows fade out.",
+            ),
+            event(
+                "show_message",
+                &[
+                    arg("text", Type::String, "The message."),
+                    arg("type", Type::Number, "0 for information, 1 for an error."),
+                ],
+                "Emitted when a message should be shown to the user.",
+            )
+            .main_frame_only(),
+            event(
+                "show_prompt",
+                &[
+                    arg("text", Type::String, "The prompt."),
+                    arg(
+                        "type",
+                        Type::Number,
+                        "0 for a question, 1 for a secret such as a password.",
+                    ),
+                    arg(
+                        "purpose",
+                        NULLABLE_STRING,
+                        "`current`, `new` or `confirm` when changing an expired password.",
+                    ),
+                ],
+                "Emitted when the user should be prompted for a response.",
+            )
+            .main_frame_only(),
+            event(
+                "user_locked",
+                &[
+                    arg("username", Type::String, "The user whose account is locked."),
+                    arg(
+                        "unlock_time",
+                        NULLABLE_NUMBER,
+                        "When the account is unlocked, in seconds since the epoch, or `null` if never.",
+                    ),
+                ],
+                "Emitted when an authentication failed and pam_faillock locked the account.",
+            )
+            .main_frame_only(),
+            event(
+                "users_changed",
+                &[],
+                "Emitted when a user is added, removed or changed.",
+            ),
+        ],
+        locals: &[],
+    },
+    Object {
+        name: "greeter_config",
+        class: "GreeterConfig",
+        doc: "The greeter configuration, available as `window.greeter_config`.",
+        calls: &[
+            getter(
+                "api",
+                Type::Object("GreeterApi"),
+                "The version of the theme API and the features this greeter provides.",
+            )
+            .cached_until(&["config_changed"]),
+            getter(
+                "banner",
+                Type::Object("GreeterBanner"),
+                "The login banner of the `banner` section of the configuration.",
+            ),
+            getter(
+                "branding",
+                Type::Object("BrandingConfig"),
+                "The `branding` section of the configuration.",
+            ),
+            getter(
+                "features",
+                Type::Object("FeaturesConfig"),
+                "The `features` section of the configuration.",
+            ),
+            getter(
+                "greeter",
+                Type::Object("GreeterSettings"),
+                "The `greeter` section of the configuration.",
+            ),
+            getter(
+                "layouts",
+                Type::Array(&Type::Class("LightDMLayout")),
+                "The keyboard layouts enabled in the configuration.",
+            ),
+            getter(
+                "theme",
+                Type::Object("ThemeConfig"),
+                "The active theme and its manifest.",
+            ),
+            method(
+                "acknowledge_banner",
+                &[],
+                Type::Bool,
+                "Report that the user acknowledged the login banner, authenticating and starting a\n\
+                 session fail until then when the configuration requires it.",
+            )
+            .main_frame_only(),
+        ],
+        events: &[event(
+            "config_changed",
+            &[],
+            "Emitted when the configuration was reloaded, in debug mode only.",
+        )],
+        locals: &[],
+    },
+    Object {
+        name: "greeter_comm",
+        class: "GreeterComm",
+        doc: "Communication between the windows of the greeter, available as `window.greeter_comm`.",
+        calls: &[
+            getter(
+                "window_metadata",
+                Type::Object("WindowMetadata"),
+                "Metadata of the window the theme is displayed in.",
+            ),
+            method(
+                "broadcast",
+                &[arg("data", Type::Any, "Any JSON serializable value.")],
+                Type::Void,
+                "Send `data` to every window, where `broadcast_received` is emitted.",
+            ),
+        ],
+        events: &[event(
+            "broadcast_received",
+            &[arg("data", Type::Any, "The data passed to `broadcast`.")],
+            "Emitted in every window when `broadcast` is called in one of them.",
+        )],
+        locals: &[],
+    },
+    Object {
+        name: "theme_utils",
+        class: "ThemeUtils",
+        doc: "Utilities for themes, available as `window.theme_utils`.",
+        calls: &[method(
+            "dirlist",
+            &[
+                arg("path", Type::String, "The absolute path of the directory."),
+                arg(
+                    "only_images",
+                    Type::Bool,
+                    "Include only images in the results.",
+                ),
+            ],
+            Type::Array(&Type::String),
+            "Returns the contents of the directory found at `path`.",
+        )],
+        events: &[],
+        locals: &[
+            Local {
+                name: "bind_this",
+                args: &[arg(
+                    "context",
+                    Type::Any,
+                    "An ES6 class instance with at least one method.",
+                )],
+                ret: Type::Any,
+                doc: "Binds `this` to class, `context`, for all of the class's methods.",
+            },
+            Local {
+                name: "dirlist",
+                args: &[
+                    arg("path", Type::String, "The absolute path of the directory."),
+                    arg(
+                        "only_images",
+                        Type::Bool,
+                        "Include only images in the results. Default `true`.",
+                    ),
+                    arg(
+                        "callback",
+                        Type::Callback(&Type::Array(&Type::String)),
+                        "Callback function to be called with the result.",
+                    ),
+                ],
+                ret: Type::Array(&Type::String),
+                doc: "Returns the contents of the directory found at `path` provided that the (normalized) `path`\n\
+                      meets at least one of the following conditions:\n  \
+                        * Is located within the greeter themes' root directory.\n  \
+                        * Has been explicitly allowed in the greeter's config file.\n  \
+                        * Is located within the greeter's shared data directory (`/var/lib/lightdm-data`).\n  \
+                        * Is located in `/tmp`.",
+            },
+            Local {
+                name: "get_current_localized_date",
+                args: &[],
+                ret: Type::String,
+                doc: "Get the current date in a localized format, using `greeter.time_language`.",
+            },
+            Local {
+                name: "get_current_localized_time",
+                args: &[],
+                ret: Type::String,
+                doc: "Get the current time in a localized format, using `greeter.time_format` when it is set.",
+            },
+        ],
+    },
+];
+
+pub static INTERFACES: &[Interface] = &[
+    Interface {
+        name: "LightDMAuthFailures",
+        class: false,
+        doc: "Failed authentications of a user, from the greeter and the pam_faillock tally.",
+        fields: &[
+            field("failures", Type::Number),
+            field("remaining", NULLABLE_NUMBER),
+            field("locked", Type::Bool),
+            field("unlock_time", NULLABLE_NUMBER),
+        ],
+    },
+    Interface {
+        name: "LightDMAuthState",
+        class: false,
+        doc: "State of the authentication flow: `idle`, `awaiting_user`, `prompting`, `verifying`, `authenticated`, `failed` or `starting_session`.",
+        fields: &[
+            field("state", Type::String),
+            field("prompt_type", NULLABLE_NUMBER),
+            field("prompt_purpose", NULLABLE_STRING),
+            field("message_type", NULLABLE_NUMBER),
+            field("attempts", Type::Number),
+            field("remote_session", NULLABLE_STRING),
+            field("password_change", Type::Bool),
+        ],
+    },
+    Interface {
+        name: "LightDMBattery",
+        class: true,
+        doc: "Battery status.",
+        fields: &[
+            field("name", Type::String),
+            field("level", Type::Number),
+            field("state", Type::String),
+            field("ac_status", Type::Bool),
+            field("capacity", Type::Number),
+            field("time", Type::String),
+            field("watt", Type::Number),
+        ],
+    },
+    Interface {
+        name: "LightDMLanguage",
+        class: true,
+        doc: "A language supported by the system.",
+        fields: &[
+            field("code", Type::String),
+            field("name", NULLABLE_STRING),
+            field("territory", NULLABLE_STRING),
+        ],
+    },
+    Interface {
+        name: "LightDMLayout",
+        class: true,
+        doc: "A keyboard layout.",
+        fields: &[
+            field("description", NULLABLE_STRING),
+            field("name", Type::String),
+            field("short_description", NULLABLE_STRING),
+        ],
+    },
+    Interface {
+        name: "LightDMSession",
+        class: true,
+        doc: "A session that can be started, with the details of its `.desktop` file.",
+        fields: &[
+            field("comment", NULLABLE_STRING),
+            field("desktop_names", Type::Array(&Type::String)),
+            field("exec_available", Type::Bool),
+            field("icon", NULLABLE_STRING),
+            field("key", Type::String),
+            field("name", NULLABLE_STRING),
+            field("try_exec_available", Type::Bool),
+            field("type", NULLABLE_STRING),
+        ],
+    },
+    Interface {
+        name: "LightDMUser",
+        class: true,
+        doc: "A user account.",
+        fields: &[
+            field("background", NULLABLE_STRING),
+            field("display_name", NULLABLE_STRING),
+            field("home_directory", NULLABLE_STRING),
+            field("image", NULLABLE_STRING),
+            field("language", NULLABLE_STRING),
+            field("layout", NULLABLE_STRING),
+            field("layouts", Type::Array(&Type::String)),
+            field("last_login", NULLABLE_NUMBER),
+            field("logged_in", Type::Bool),
+            field("session", NULLABLE_STRING),
+            field("username", Type::String),
+        ],
+    },
+    Interface {
+        name: "LightDMLastState",
+        class: false,
+        doc: "State remembered by the greeter across logins.",
+        fields: &[
+            field("version", Type::Number),
+            field("user", NULLABLE_STRING),
+            field("users", Type::Array(&Type::Object("LightDMUserState"))),
+        ],
+    },
+    Interface {
+        name: "LightDMUserState",
+        class: false,
+        doc: "What a user last logged in with.",
+        fields: &[
+            field("username", Type::String),
+            field("session", NULLABLE_STRING),
+            field("language", NULLABLE_STRING),
+            field("layout", NULLABLE_STRING),
+        ],
+    },
+    Interface {
+        name: "GreeterApi",
+        class: false,
+        doc: "Semantic version of the theme API and optional features enabled in the greeter.",
+        fields: &[
+            field("version", Type::String),
+            field("greeter_version", Type::String),
+            field("build", Type::Object("GreeterBuild")),
+            field("backend", Type::String),
+            field("features", Type::Array(&Type::String)),
+        ],
+    },
+    Interface {
+        name: "GreeterBuild",
+        class: false,
+        doc: "How the greeter was built.",
+        fields: &[
+            field("profile", Type::String),
+            field("arch", Type::String),
+            field("os", Type::String),
+        ],
+    },
+    Interface {
+        name: "BrandingConfig",
+        class: false,
+        doc: "The `branding` section of the configuration.",
+        fields: &[
+            field("background_images_dir", Type::String),
+            field("logo_image", Type::String),
+            field("user_image", Type::String),
+        ],
+    },
+    Interface {
+        name: "GreeterBanner",
+        class: false,
+        doc: "The login banner, `format` is `text` or `markdown`.",
+        fields: &[
+            field("text", NULLABLE_STRING),
+            field("format", Type::String),
+            field("acknowledgement_required", Type::Bool),
+            field("acknowledged", Type::Bool),
+        ],
+    },
+    Interface {
+        name: "GreeterSettings",
+        class: false,
+        doc: "The `greeter` section of the configuration.",
+        fields: &[
+            field("debug_mode", Type::Bool),
+            field("detect_theme_errors", Type::Bool),
+            field("screensaver_timeout", Type::Number),
+            field("session_fade_out", Type::Number),
+            field("faillock_dir", Type::String),
+            field("secure_mode", Type::Bool),
+            field("theme", Type::String),
+            field("icon_theme", Type::String),
+            field("time_language", Type::String),
+            field("time_format", Type::String),
+        ],
+    },
+    Interface {
+        name: "BacklightConfig",
+        class: false,
+        doc: "The `features.backlight` section of the configuration.",
+        fields: &[
+            field("enabled", Type::Bool),
+            field("value", Type::Number),
+            field("steps", Type::Number),
+        ],
+    },
+    Interface {
+        name: "FeaturesConfig",
+        class: false,
+        doc: "The `features` section of the configuration.",
+        fields: &[
+            field("battery", Type::Bool),
+            field("backlight", Type::Object("BacklightConfig")),
+            field("fingerprint", Type::Bool),
+        ],
+    },
+    Interface {
+        name: "ThemeConfig",
+        class: false,
+        doc: "The active theme.",
+        fields: &[
+            field("primary_html", Type::String),
+            field("secondary_html", NULLABLE_STRING),
+            field("legacy_api", Type::Bool),
+            field("min_api_version", NULLABLE_STRING),
+        ],
+    },
+    Interface {
+        name: "WindowPosition",
+        class: false,
+        doc: "Position of a window.",
+        fields: &[field("x", Type::Number), field("y", Type::Number)],
+    },
+    Interface {
+        name: "WindowSize",
+        class: false,
+        doc: "Size of a window.",
+        fields: &[field("width", Type::Number), field("height", Type::Number)],
+    },
+    Interface {
+        name: "WindowBoundary",
+        class: false,
+        doc: "Boundary of all the windows of the greeter.",
+        fields: &[
+            field("minX", Type::Number),
+            field("minY", Type::Number),
+            field("maxX", Type::Number),
+            field("maxY", Type::Number),
+        ],
+    },
+    Interface {
+        name: "WindowMetadata",
+        class: false,
+        doc: "Metadata of a greeter window.",
+        fields: &[
+            field("id", Type::Number),
+            field("is_primary", Type::Bool),
+            field("position", Type::Object("WindowPosition")),
+            field("size", Type::Object("WindowSize")),
+            field("overallBoundary", Type::Object("WindowBoundary")),
+        ],
+    },
+];
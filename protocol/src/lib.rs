@@ -0,0 +1,20 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Definition of every call and event exchanged between the theme API in the web process
+//! and the greeter, from which the theme API classes and typings are generated
+
+mod codegen;
+mod definition;
+mod message;
+mod schema;
+mod version;
+
+pub use codegen::{javascript, typescript};
+pub use definition::{INTERFACES, OBJECTS};
+pub use message::{EVENT_MESSAGE, Error, Reply, Request};
+pub use schema::{
+    Arg, Call, Event, Field, Interface, Kind, Local, Object, Type, interface, object,
+};
+pub use version::{API_VERSION, api_compatible};
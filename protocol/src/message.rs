@@ -0,0 +1,441 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+use std::fmt;
+
+use crate::schema::{Arg, Call, Event, Kind, object};
+
+/// Name of the user messages carrying events from the greeter to the web process, their
+/// parameters are `(target, event, args)` where `args` is the JSON array of the arguments
+pub const EVENT_MESSAGE: &str = "event";
+
+/// A call of the theme API, sent from the web process to the greeter
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Request {
+    pub target: String,
+    pub method: String,
+    pub kind: Kind,
+    #[serde(default)]
+    pub args: Vec<Value>,
+    /// Whether the call was made by the top-level frame of the theme
+    #[serde(default)]
+    pub main_frame: bool,
+}
+
+/// Result of a request, sent back to the web process
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reply {
+    Ok(Value),
+    Error(String),
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Malformed(String),
+    UnknownTarget(String),
+    UnknownCall {
+        target: String,
+        method: String,
+        kind: Kind,
+    },
+    UnknownEvent {
+        target: String,
+        name: String,
+    },
+    MainFrameOnly(String),
+    TooManyArguments {
+        call: String,
+        expected: usize,
+        found: usize,
+    },
+    InvalidArgument {
+        call: String,
+        arg: &'static str,
+        expected: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Malformed(e) => write!(f, "malformed request: {e}"),
+            Error::UnknownTarget(target) => write!(f, "unknown object `{target}`"),
+            Error::UnknownCall {
+                target,
+                method,
+                kind,
+            } => write!(f, "{target}.{method} is not a known {kind}"),
+            Error::UnknownEvent { target, name } => {
+                write!(f, "{target}.{name} is not a known event")
+            }
+            Error::MainFrameOnly(call) => {
+                write!(f, "{call} is only available to the top-level frame")
+            }
+            Error::TooManyArguments {
+                call,
+                expected,
+                found,
+            } => write!(f, "{call} takes {expected} argument(s), {found} given"),
+            Error::InvalidArgument {
+                call,
+                arg,
+                expected,
+            } => write!(f, "{call}: `{arg}` must be {expected}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Request {
+    pub fn new(target: &str, method: &str, kind: Kind, args: Vec<Value>, main_frame: bool) -> Self {
+        Self {
+            target: target.to_string(),
+            method: method.to_string(),
+            kind,
+            args,
+            main_frame,
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(|e| Error::Malformed(e.to_string()))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Request is serializable")
+    }
+
+    /// Look up the call in the protocol definition and check the arguments against it, and the
+    /// frame it was made by if it is restricted to the top-level one
+    pub fn resolve(&self) -> Result<&'static Call, Error> {
+        let object =
+            object(&self.target).ok_or_else(|| Error::UnknownTarget(self.target.clone()))?;
+        let call = object
+            .call(&self.method, self.kind)
+            .ok_or_else(|| Error::UnknownCall {
+                target: self.target.clone(),
+                method: self.method.clone(),
+                kind: self.kind,
+            })?;
+        let name = format!("{}.{}", self.target, self.method);
+        if call.main_frame_only && !self.main_frame {
+            return Err(Error::MainFrameOnly(name));
+        }
+        check_args(&name, call.args, &self.args)?;
+        Ok(call)
+    }
+
+    /// The argument at `index` deserialized as `T`, `None` if it is missing, null or mistyped
+    pub fn arg<T: DeserializeOwned>(&self, index: usize) -> Option<T> {
+        self.args
+            .get(index)
+            .filter(|value| !value.is_null())
+            .and_then(|value| T::deserialize(value).ok())
+    }
+}
+
+impl Reply {
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(|e| Error::Malformed(e.to_string()))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Reply is serializable")
+    }
+}
+
+impl Event {
+    /// Check the arguments of an emission of this event
+    pub fn check(&self, args: &[Value]) -> Result<(), Error> {
+        check_args(self.name, self.args, args)
+    }
+}
+
+fn check_args(call: &str, expected: &[Arg], args: &[Value]) -> Result<(), Error> {
+    if args.len() > expected.len() {
+        return Err(Error::TooManyArguments {
+            call: call.to_string(),
+            expected: expected.len(),
+            found: args.len(),
+        });
+    }
+
+    // Missing trailing arguments are undefined, which JSON can only represent as null
+    for (index, arg) in expected.iter().enumerate() {
+        if !arg.ty.accepts(args.get(index).unwrap_or(&Value::Null)) {
+            return Err(Error::InvalidArgument {
+                call: call.to_string(),
+                arg: arg.name,
+                expected: arg.ty.notation(),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::schema::object;
+
+    fn call(
+        target: &str,
+        method: &str,
+        kind: Kind,
+        args: Vec<Value>,
+    ) -> Result<&'static str, Error> {
+        Request::new(target, method, kind, args, true)
+            .resolve()
+            .map(|call| call.name)
+    }
+
+    #[test]
+    fn known_calls_resolve() {
+        assert_eq!(
+            call("lightdm", "brightness", Kind::Get, vec![]).unwrap(),
+            "brightness"
+        );
+        assert_eq!(
+            call("lightdm", "brightness", Kind::Set, vec![json!(50)]).unwrap(),
+            "brightness"
+        );
+        assert_eq!(
+            call("lightdm", "auth_failures", Kind::Call, vec![json!("alice")]).unwrap(),
+            "auth_failures"
+        );
+    }
+
+    #[test]
+    fn unknown_calls_are_rejected() {
+        assert!(matches!(
+            call("lightdmx", "brightness", Kind::Get, vec![]),
+            Err(Error::UnknownTarget(target)) if target == "lightdmx"
+        ));
+        assert!(matches!(
+            call("lightdm", "self_destruct", Kind::Call, vec![]),
+            Err(Error::UnknownCall { method, .. }) if method == "self_destruct"
+        ));
+    }
+
+    #[test]
+    fn methods_are_not_properties() {
+        for kind in [Kind::Get, Kind::Set] {
+            assert!(matches!(
+                call("lightdm", "shutdown", kind, vec![]),
+                Err(Error::UnknownCall { kind: found, .. }) if found == kind
+            ));
+        }
+        // Nor properties methods
+        assert!(matches!(
+            call("lightdm", "brightness", Kind::Call, vec![]),
+            Err(Error::UnknownCall { .. })
+        ));
+    }
+
+    #[test]
+    fn argument_count_is_checked() {
+        assert!(matches!(
+            call("lightdm", "shutdown", Kind::Call, vec![json!(true)]),
+            Err(Error::TooManyArguments {
+                expected: 0,
+                found: 1,
+                ..
+            })
+        ));
+        assert!(matches!(
+            call(
+                "lightdm",
+                "respond",
+                Kind::Call,
+                vec![json!("a"), json!("b")]
+            ),
+            Err(Error::TooManyArguments {
+                expected: 1,
+                found: 2,
+                ..
+            })
+        ));
+        // A missing argument is undefined, only accepted if it is nullable
+        assert!(matches!(
+            call("lightdm", "respond", Kind::Call, vec![]),
+            Err(Error::InvalidArgument {
+                arg: "response",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn argument_types_are_checked() {
+        assert!(matches!(
+            call("lightdm", "respond", Kind::Call, vec![json!(1234)]),
+            Err(Error::InvalidArgument { arg: "response", expected, .. }) if expected == "string"
+        ));
+        assert!(matches!(
+            call("lightdm", "brightness", Kind::Set, vec![json!("50")]),
+            Err(Error::InvalidArgument { arg: "value", .. })
+        ));
+        assert!(matches!(
+            call(
+                "theme_utils",
+                "dirlist",
+                Kind::Call,
+                vec![json!("/usr/share"), json!(null)]
+            ),
+            Err(Error::InvalidArgument {
+                arg: "only_images",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn nullable_arguments_are_optional() {
+        for args in [vec![], vec![json!(null)], vec![json!(10)]] {
+            assert!(call("lightdm", "brightness_increase", Kind::Call, args).is_ok());
+        }
+        assert!(
+            call(
+                "lightdm",
+                "brightness_increase",
+                Kind::Call,
+                vec![json!(true)]
+            )
+            .is_err()
+        );
+
+        for args in [
+            vec![json!("rdp")],
+            vec![json!("rdp"), json!(null)],
+            vec![json!("rdp"), json!("alice")],
+        ] {
+            assert!(call("lightdm", "authenticate_remote", Kind::Call, args).is_ok());
+        }
+        // Only trailing arguments may be left out
+        assert!(
+            call(
+                "lightdm",
+                "authenticate_remote",
+                Kind::Call,
+                vec![json!(null)]
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn main_frame_only_calls_are_rejected_from_other_frames() {
+        let request = Request::new(
+            "lightdm",
+            "respond",
+            Kind::Call,
+            vec![json!("secret")],
+            false,
+        );
+        assert!(
+            matches!(request.resolve(), Err(Error::MainFrameOnly(call)) if call == "lightdm.respond")
+        );
+
+        let request = Request::new("lightdm", "brightness", Kind::Get, vec![], false);
+        assert!(request.resolve().is_ok());
+
+        // Requests which do not tell are not from the main frame
+        let request = Request::from_json(
+            r#"{"target":"lightdm","method":"respond","kind":"call","args":["secret"]}"#,
+        )
+        .unwrap();
+        assert!(!request.main_frame);
+        assert!(request.resolve().is_err());
+    }
+
+    #[test]
+    fn requests_round_trip() {
+        let request = Request::new(
+            "lightdm",
+            "respond",
+            Kind::Call,
+            vec![json!("secret")],
+            true,
+        );
+        let decoded = Request::from_json(&request.to_json()).unwrap();
+        assert_eq!(decoded.target, "lightdm");
+        assert_eq!(decoded.method, "respond");
+        assert_eq!(decoded.kind, Kind::Call);
+        assert_eq!(decoded.args, [json!("secret")]);
+        assert!(decoded.main_frame);
+
+        assert!(matches!(Request::from_json("{"), Err(Error::Malformed(_))));
+        assert!(matches!(
+            Request::from_json(r#"{"target":"lightdm","method":"respond","kind":"poke"}"#),
+            Err(Error::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn request_args_are_typed() {
+        let request = Request::new(
+            "lightdm",
+            "authenticate_remote",
+            Kind::Call,
+            vec![json!("rdp"), json!(null)],
+            true,
+        );
+        assert_eq!(request.arg::<String>(0).as_deref(), Some("rdp"));
+        assert_eq!(request.arg::<String>(1), None);
+        assert_eq!(request.arg::<String>(2), None);
+        assert_eq!(request.arg::<u32>(0), None);
+    }
+
+    #[test]
+    fn event_payloads_are_checked() {
+        let lightdm = object("lightdm").unwrap();
+        let show_prompt = lightdm.event("show_prompt").unwrap();
+        assert!(
+            show_prompt
+                .check(&[json!("Password:"), json!(1), json!(null)])
+                .is_ok()
+        );
+        assert!(show_prompt.check(&[json!("Password:"), json!(1)]).is_ok());
+        assert!(matches!(
+            show_prompt.check(&[json!("Password:"), json!("secret")]),
+            Err(Error::InvalidArgument { arg: "type", .. })
+        ));
+        assert!(matches!(
+            show_prompt.check(&[json!("Password:"), json!(1), json!(null), json!(0)]),
+            Err(Error::TooManyArguments { .. })
+        ));
+
+        // Object arguments are checked field by field
+        let auth_state_changed = lightdm.event("auth_state_changed").unwrap();
+        let state = json!({
+            "state": "prompting",
+            "prompt_type": 1,
+            "prompt_purpose": null,
+            "message_type": null,
+            "attempts": 0,
+            "remote_session": null,
+            "password_change": false,
+        });
+        let mut missing = state.clone();
+        missing.as_object_mut().unwrap().remove("attempts");
+        assert!(auth_state_changed.check(&[missing]).is_err());
+        assert!(auth_state_changed.check(&[state]).is_ok());
+        assert!(auth_state_changed.check(&[json!("prompting")]).is_err());
+    }
+
+    #[test]
+    fn replies_round_trip() {
+        let reply = Reply::from_json(&Reply::Ok(json!([1, 2])).to_json()).unwrap();
+        assert!(matches!(reply, Reply::Ok(value) if value == json!([1, 2])));
+        let reply = Reply::from_json(&Reply::Error("denied".to_string()).to_json()).unwrap();
+        assert!(matches!(reply, Reply::Error(e) if e == "denied"));
+    }
+}
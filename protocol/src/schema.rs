@@ -0,0 +1,314 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use std::fmt;
+
+use crate::definition::{INTERFACES, OBJECTS};
+
+/// How a call is exposed to themes
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    /// Property getter
+    Get,
+    /// Property setter, with a single `value` argument
+    Set,
+    /// Method
+    Call,
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Kind::Get => write!(f, "getter"),
+            Kind::Set => write!(f, "setter"),
+            Kind::Call => write!(f, "method"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Type {
+    Any,
+    Void,
+    Bool,
+    Number,
+    String,
+    Nullable(&'static Type),
+    Array(&'static Type),
+    /// Plain object described by the interface of that name
+    Object(&'static str),
+    /// Object wrapped in the generated class of that name
+    Class(&'static str),
+    /// Function called with a single value, only used by local methods
+    Callback(&'static Type),
+}
+
+impl Type {
+    /// Whether `value` is a valid JSON representation of this type
+    pub fn accepts(&self, value: &Value) -> bool {
+        match self {
+            Type::Any => true,
+            Type::Void => value.is_null(),
+            Type::Bool => value.is_boolean(),
+            Type::Number => value.is_number(),
+            Type::String => value.is_string(),
+            Type::Nullable(ty) => value.is_null() || ty.accepts(value),
+            Type::Array(ty) => value
+                .as_array()
+                .is_some_and(|values| values.iter().all(|value| ty.accepts(value))),
+            Type::Object(name) | Type::Class(name) => match (value, interface(name)) {
+                (Value::Object(object), Some(interface)) => interface.fields.iter().all(|field| {
+                    field
+                        .ty
+                        .accepts(object.get(field.name).unwrap_or(&Value::Null))
+                }),
+                (Value::Object(_), None) => true,
+                _ => false,
+            },
+            Type::Callback(_) => false,
+        }
+    }
+
+    pub fn is_nullable(&self) -> bool {
+        matches!(self, Type::Any | Type::Void | Type::Nullable(_))
+    }
+
+    /// The type in JSDoc and TypeScript notation
+    pub fn notation(&self) -> String {
+        match self {
+            Type::Any => "any".to_string(),
+            Type::Void => "void".to_string(),
+            Type::Bool => "boolean".to_string(),
+            Type::Number => "number".to_string(),
+            Type::String => "string".to_string(),
+            Type::Nullable(ty) => format!("{} | null", ty.notation()),
+            Type::Array(ty @ (Type::Nullable(_) | Type::Callback(_))) => {
+                format!("({})[]", ty.notation())
+            }
+            Type::Array(ty) => format!("{}[]", ty.notation()),
+            Type::Object(name) | Type::Class(name) => name.to_string(),
+            Type::Callback(ty) => format!("(value: {}) => void", ty.notation()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Arg {
+    pub name: &'static str,
+    pub ty: Type,
+    pub doc: &'static str,
+}
+
+/// A request the web process can send to the greeter
+#[derive(Debug)]
+pub struct Call {
+    pub name: &'static str,
+    pub kind: Kind,
+    pub args: &'static [Arg],
+    pub ret: Type,
+    pub doc: &'static str,
+    /// Only available to the top-level frame of a theme, not to the frames it embeds. The greeter
+    /// rejects the requests of other frames
+    pub main_frame_only: bool,
+    /// Getter whose value the web process caches, until one of these events of the same object
+    /// is emitted, or of another object if written `<object>:<event>`
+    pub cached_until: Option<&'static [&'static str]>,
+}
+
+/// A signal the greeter emits to the web process
+#[derive(Debug)]
+pub struct Event {
+    pub name: &'static str,
+    pub args: &'static [Arg],
+    pub doc: &'static str,
+    /// Only delivered to the top-level frame of a theme, not to the frames it embeds
+    pub main_frame_only: bool,
+}
+
+impl Call {
+    pub const fn main_frame_only(self) -> Self {
+        Self {
+            main_frame_only: true,
+            ..self
+        }
+    }
+
+    pub const fn cached_until(self, events: &'static [&'static str]) -> Self {
+        Self {
+            cached_until: Some(events),
+            ..self
+        }
+    }
+}
+
+impl Event {
+    pub const fn main_frame_only(self) -> Self {
+        Self {
+            main_frame_only: true,
+            ..self
+        }
+    }
+}
+
+/// A method implemented in the theme API script, without a round trip to the greeter
+#[derive(Debug)]
+pub struct Local {
+    pub name: &'static str,
+    pub args: &'static [Arg],
+    pub ret: Type,
+    pub doc: &'static str,
+}
+
+/// A global object exposed to themes
+#[derive(Debug)]
+pub struct Object {
+    /// Name of the global, which is also the target of its requests
+    pub name: &'static str,
+    pub class: &'static str,
+    pub calls: &'static [Call],
+    pub events: &'static [Event],
+    pub locals: &'static [Local],
+    pub doc: &'static str,
+}
+
+impl Object {
+    pub fn call(&self, name: &str, kind: Kind) -> Option<&'static Call> {
+        self.calls
+            .iter()
+            .find(|call| call.name == name && call.kind == kind)
+    }
+
+    pub fn event(&self, name: &str) -> Option<&'static Event> {
+        self.events.iter().find(|event| event.name == name)
+    }
+}
+
+#[derive(Debug)]
+pub struct Field {
+    pub name: &'static str,
+    pub ty: Type,
+}
+
+/// Shape of an object passed through the bridge
+#[derive(Debug)]
+pub struct Interface {
+    pub name: &'static str,
+    pub fields: &'static [Field],
+    /// Generate a class wrapping the object, rather than only a type declaration
+    pub class: bool,
+    pub doc: &'static str,
+}
+
+pub fn object(name: &str) -> Option<&'static Object> {
+    OBJECTS.iter().find(|object| object.name == name)
+}
+
+pub fn interface(name: &str) -> Option<&'static Interface> {
+    INTERFACES.iter().find(|interface| interface.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn scalar_types() {
+        assert!(Type::Bool.accepts(&json!(false)));
+        assert!(!Type::Bool.accepts(&json!(0)));
+        assert!(Type::Number.accepts(&json!(-1.5)));
+        assert!(!Type::Number.accepts(&json!("1")));
+        assert!(Type::String.accepts(&json!("")));
+        assert!(!Type::String.accepts(&json!(null)));
+        assert!(Type::Void.accepts(&json!(null)));
+        assert!(!Type::Void.accepts(&json!(0)));
+        assert!(Type::Any.accepts(&json!({ "any": [1] })));
+    }
+
+    #[test]
+    fn nullable_types() {
+        const NULLABLE: Type = Type::Nullable(&Type::Number);
+        assert!(NULLABLE.accepts(&json!(null)));
+        assert!(NULLABLE.accepts(&json!(3)));
+        assert!(!NULLABLE.accepts(&json!("3")));
+        assert!(NULLABLE.is_nullable());
+        assert!(!Type::Number.is_nullable());
+    }
+
+    #[test]
+    fn array_types() {
+        const STRINGS: Type = Type::Array(&Type::String);
+        assert!(STRINGS.accepts(&json!([])));
+        assert!(STRINGS.accepts(&json!(["a", "b"])));
+        assert!(!STRINGS.accepts(&json!(["a", 1])));
+        assert!(!STRINGS.accepts(&json!("a")));
+    }
+
+    #[test]
+    fn object_types() {
+        const FAILURES: Type = Type::Object("LightDMAuthFailures");
+        assert!(FAILURES.accepts(&json!({
+            "failures": 2,
+            "remaining": null,
+            "locked": false,
+            "unlock_time": null,
+        })));
+        // Nullable fields may be left out, others not
+        assert!(FAILURES.accepts(&json!({ "failures": 2, "locked": false })));
+        assert!(!FAILURES.accepts(&json!({ "failures": 2 })));
+        assert!(!FAILURES.accepts(&json!({ "failures": "2", "locked": false })));
+        assert!(!FAILURES.accepts(&json!([2, false])));
+
+        // Objects of interfaces not described are not checked
+        assert!(Type::Object("Unknown").accepts(&json!({ "anything": true })));
+        assert!(!Type::Callback(&Type::Any).accepts(&json!(null)));
+    }
+
+    #[test]
+    fn notation() {
+        const NULLABLE_STRINGS: Type = Type::Array(&Type::Nullable(&Type::String));
+        assert_eq!(NULLABLE_STRINGS.notation(), "(string | null)[]");
+        assert_eq!(
+            Type::Nullable(&Type::Class("LightDMBattery")).notation(),
+            "LightDMBattery | null"
+        );
+        assert_eq!(
+            Type::Callback(&Type::Array(&Type::String)).notation(),
+            "(value: string[]) => void"
+        );
+    }
+
+    #[test]
+    fn definition_is_consistent() {
+        for object in OBJECTS {
+            for call in object.calls {
+                if call.kind == Kind::Set {
+                    assert!(
+                        object.call(call.name, Kind::Get).is_some(),
+                        "{}.{} has a setter without a getter",
+                        object.name,
+                        call.name
+                    );
+                    assert_eq!(call.args.len(), 1, "{}.{}", object.name, call.name);
+                }
+                for event in call.cached_until.unwrap_or_default() {
+                    let (owner, event) = event.split_once(':').unwrap_or((object.name, event));
+                    assert!(
+                        crate::schema::object(owner)
+                            .and_then(|owner| owner.event(event))
+                            .is_some(),
+                        "{}.{} is cached until the unknown event {owner}.{event}",
+                        object.name,
+                        call.name
+                    );
+                }
+            }
+        }
+    }
+}
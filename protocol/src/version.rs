@@ -0,0 +1,76 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+/// Semantic version of the theme API. The major version is bumped when a call or an event is
+/// removed or changed in an incompatible way, the minor version when one is added.
+pub const API_VERSION: &str = "1.1.0";
+
+/// Whether the theme API of this greeter is compatible with the version `required` by a theme,
+/// `None` if `required` is not a `major[.minor[.patch]]` version
+pub fn api_compatible(required: &str) -> Option<bool> {
+    compatible(API_VERSION, required)
+}
+
+fn compatible(provided: &str, required: &str) -> Option<bool> {
+    let provided = parse(provided)?;
+    let required = parse(required)?;
+    Some(provided.0 == required.0 && provided >= required)
+}
+
+fn parse(version: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = version.trim().trim_start_matches('v').split('.');
+    let mut next = || parts.next().map(str::parse::<u64>).transpose().ok();
+    let version = (next()??, next()?.unwrap_or(0), next()?.unwrap_or(0));
+    parts.next().is_none().then_some(version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn current_version_is_compatible() {
+        assert!(parse(API_VERSION).is_some());
+        assert_eq!(api_compatible(API_VERSION), Some(true));
+    }
+
+    #[test]
+    fn equal_and_older_versions_are_compatible() {
+        for required in ["1.4.2", "v1.4.2", " 1.4.2 ", "1.4.0", "1.3.9", "1.0.0"] {
+            assert_eq!(compatible("1.4.2", required), Some(true), "{required}");
+        }
+    }
+
+    #[test]
+    fn newer_versions_are_incompatible() {
+        for required in ["1.4.3", "1.5.0", "1.10"] {
+            assert_eq!(compatible("1.4.2", required), Some(false), "{required}");
+        }
+    }
+
+    #[test]
+    fn other_major_versions_are_incompatible() {
+        for required in ["0.9.0", "0", "2.0.0", "2"] {
+            assert_eq!(compatible("1.4.2", required), Some(false), "{required}");
+        }
+    }
+
+    #[test]
+    fn missing_components_are_zero() {
+        assert_eq!(parse("1"), Some((1, 0, 0)));
+        assert_eq!(parse("1.4"), Some((1, 4, 0)));
+        assert_eq!(compatible("1.4.2", "1"), Some(true));
+        assert_eq!(compatible("1.4.2", "1.4"), Some(true));
+        assert_eq!(compatible("1.4.0", "1.4"), Some(true));
+        assert_eq!(compatible("1.3.5", "1.4"), Some(false));
+    }
+
+    #[test]
+    fn garbage_is_not_a_version() {
+        for required in ["", "x", "1.x", "1..0", "1.0.0.1", "1.0-beta", "-1", "1.0."] {
+            assert_eq!(parse(required), None, "{required}");
+            assert_eq!(compatible("1.4.2", required), None, "{required}");
+        }
+    }
+}
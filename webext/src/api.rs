@@ -0,0 +1,509 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use gtk::glib::{self, MainContext, WeakRef, clone, prelude::*, variant::ToVariant};
+use protocol::{Call, Kind, Object, Reply, Request, Type};
+
+use ext::prelude::*;
+
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use crate::cache::Cache;
+
+/// The theme API of a frame.
+///
+/// `context` is the page world the theme runs in. `bridge` is an isolated world of the same frame
+/// for the scripting the bridge does on its own, which the theme can't tamper with.
+pub(crate) struct Api {
+    page: WeakRef<wwpe::WebPage>,
+    pub(crate) context: jsc::Context,
+    bridge: jsc::Context,
+    main_frame: bool,
+    /// Constructors of the classes defined by the prelude, looked up before any theme script runs
+    classes: HashMap<&'static str, jsc::Value>,
+    /// `CustomEvent`, `EventTarget.prototype.dispatchEvent` and `Reflect.apply` of the page world,
+    /// looked up before any theme script runs so that the theme can't shadow them
+    custom_event: Option<jsc::Value>,
+    dispatch_event: Option<jsc::Value>,
+    apply: Option<jsc::Value>,
+    cache: RefCell<Cache>,
+}
+
+impl Api {
+    pub(crate) fn new(
+        page: &wwpe::WebPage,
+        context: jsc::Context,
+        bridge: jsc::Context,
+        main_frame: bool,
+        debug: bool,
+    ) -> Rc<Self> {
+        // Class declarations are bindings of the global scope rather than properties of `window`
+        let classes = protocol::INTERFACES
+            .iter()
+            .filter(|interface| interface.class)
+            .map(|interface| interface.name)
+            .chain(["LightDMSignal"])
+            .filter_map(|name| {
+                context
+                    .evaluate(name)
+                    .filter(|constructor| constructor.is_constructor())
+                    .map(|constructor| (name, constructor))
+            })
+            .collect();
+        let function = |code: &str| {
+            context
+                .evaluate(code)
+                .filter(|function| function.is_function())
+        };
+        let custom_event = function("CustomEvent");
+        let dispatch_event = function("EventTarget.prototype.dispatchEvent");
+        let apply = function("Reflect.apply");
+
+        Rc::new(Self {
+            page: page.downgrade(),
+            context,
+            bridge,
+            main_frame,
+            classes,
+            custom_event,
+            dispatch_event,
+            apply,
+            cache: RefCell::new(Cache::new(debug)),
+        })
+    }
+
+    /// Register the theme API objects as native classes and create their instances.
+    ///
+    /// Getters and setters become properties of the classes and methods call the greeter through
+    /// the bridge. Events are `LightDMSignal`s. Calls and events restricted to the main frame are
+    /// left out of embedded frames, the greeter rejects their requests as well.
+    pub(crate) fn install(self: &Rc<Self>) {
+        let context = &self.context;
+        let Some(global) = context.global_object() else {
+            return;
+        };
+
+        for object in protocol::OBJECTS {
+            let Some(class) = context.register_class(object.class, None) else {
+                glib::g_warning!("", "Failed to register class {}", object.class);
+                continue;
+            };
+
+            for call in object.calls {
+                if call.main_frame_only && !self.main_frame {
+                    continue;
+                }
+                match call.kind {
+                    Kind::Get => self.add_property(&class, object, call),
+                    Kind::Set => {}
+                    Kind::Call => self.add_method(&class, object, call),
+                }
+            }
+
+            // The instance of a class only tells it apart from the others
+            let instance = jsc::Value::new_object(
+                context,
+                Some(jsc::Value::new_string(context, Some(object.name))),
+                Some(&class),
+            );
+            for event in object.events {
+                if event.main_frame_only && !self.main_frame {
+                    continue;
+                }
+                let name = jsc::Value::new_string(context, Some(event.name));
+                if let Some(signal) = self.construct("LightDMSignal", &name) {
+                    instance.object_set_property(event.name, &signal);
+                }
+            }
+            global.object_set_property(object.name, &instance);
+        }
+    }
+
+    /// Freeze the theme API objects, their signals and the classes they use, and make the globals
+    /// holding them read-only, so that scripts of the theme can't replace any part of them
+    pub(crate) fn freeze(&self) {
+        let context = &self.context;
+        let (Some(global), Some(object_class)) = (
+            context.global_object(),
+            context
+                .evaluate("Object")
+                .filter(|object_class| object_class.is_object()),
+        ) else {
+            return;
+        };
+        let freeze = |value: &jsc::Value| {
+            let _ = object_class.object_invoke_methodv("freeze", &[value.clone()]);
+        };
+
+        for class in self.classes.values() {
+            if let Some(prototype) = class.object_get_property("prototype") {
+                freeze(&prototype);
+            }
+            freeze(class);
+        }
+
+        for object in protocol::OBJECTS {
+            let Some(instance) = global.object_get_property(object.name) else {
+                continue;
+            };
+            for event in object.events {
+                if let Some(signal) = instance.object_get_property(event.name)
+                    && signal.is_object()
+                {
+                    freeze(&signal);
+                }
+            }
+            if let Some(prototype) =
+                object_class.object_invoke_methodv("getPrototypeOf", &[instance.clone()])
+            {
+                freeze(&prototype);
+            }
+            freeze(&instance);
+            global.object_define_property_data(
+                object.name,
+                jsc::ValuePropertyFlags::ENUMERABLE,
+                Some(&instance),
+            );
+        }
+    }
+
+    fn add_property(
+        self: &Rc<Self>,
+        class: &jsc::Class,
+        object: &'static Object,
+        getter: &'static Call,
+    ) {
+        let setter = object
+            .call(getter.name, Kind::Set)
+            .filter(|setter| !setter.main_frame_only || self.main_frame);
+        class.add_property(
+            getter.name,
+            true,
+            setter.is_some(),
+            clone!(
+                #[strong(rename_to = api)]
+                self,
+                move |_, value| match (value, setter) {
+                    (Some(value), Some(setter)) => {
+                        api.request(object, setter, &[value]);
+                        None
+                    }
+                    (Some(_), None) => None,
+                    // Getters must return a value, a failed request has thrown an exception
+                    (None, _) => Some(
+                        api.request(object, getter, &[])
+                            .unwrap_or_else(|| jsc::Value::new_undefined(&api.context)),
+                    ),
+                }
+            ),
+        );
+    }
+
+    fn add_method(
+        self: &Rc<Self>,
+        class: &jsc::Class,
+        object: &'static Object,
+        call: &'static Call,
+    ) {
+        match (object.name, call.name) {
+            ("theme_utils", "dirlist") => class.add_method_variadic(
+                call.name,
+                clone!(
+                    #[strong(rename_to = api)]
+                    self,
+                    move |_, args| api.dirlist(object, call, args)
+                ),
+            ),
+            _ => class.add_method_variadic(
+                call.name,
+                clone!(
+                    #[strong(rename_to = api)]
+                    self,
+                    move |_, args| api.request(object, call, args)
+                ),
+            ),
+        }
+    }
+
+    /// Send a request to the greeter, `None` if it failed, in which case an exception is thrown
+    fn request(
+        &self,
+        object: &'static Object,
+        call: &'static Call,
+        args: &[jsc::Value],
+    ) -> Option<jsc::Value> {
+        let context = &self.context;
+        let value = if call.cached_until.is_some() {
+            self.cached(object, call)?
+        } else {
+            let args = match args
+                .iter()
+                .map(ext::from_jscvalue::<serde_json::Value>)
+                .collect::<Result<Vec<_>, _>>()
+            {
+                Ok(args) => args,
+                Err(e) => {
+                    context.throw(&format!("{}.{}: {e}", object.name, call.name));
+                    return None;
+                }
+            };
+            self.send(
+                object,
+                call,
+                args,
+                self.main_frame && self.called_by_own_frame(),
+            )?
+        };
+
+        match value.and_then(|value| ext::to_jscvalue(context, &value).map_err(|e| e.to_string())) {
+            Ok(value) => Some(self.wrap(&call.ret, value)),
+            Err(e) => {
+                context.throw(&e);
+                None
+            }
+        }
+    }
+
+    /// The value of a cached getter, only requested from the greeter on a cache miss
+    fn cached(
+        &self,
+        object: &'static Object,
+        getter: &'static Call,
+    ) -> Option<Result<serde_json::Value, String>> {
+        if let Some(value) = self.cache.borrow_mut().get(object, getter) {
+            return Some(Ok(value));
+        }
+
+        let version = self.cache.borrow().version();
+        let value = self.send(object, getter, vec![], self.main_frame)?;
+        if let Ok(value) = &value {
+            self.cache
+                .borrow_mut()
+                .insert(version, object, getter, value.clone());
+        }
+        Some(value)
+    }
+
+    /// Request the values of the cached getters not cached yet
+    pub(crate) fn fill_cache(&self) {
+        for object in protocol::OBJECTS {
+            for getter in object.calls.iter().filter(|call| {
+                call.kind == Kind::Get
+                    && call.cached_until.is_some()
+                    && (!call.main_frame_only || self.main_frame)
+            }) {
+                let _ = self.cached(object, getter);
+            }
+        }
+    }
+
+    /// Whether the script calling the theme API runs in this frame. An embedded frame of the same
+    /// origin can reach the objects of the main frame through `window.top`, their handlers are
+    /// then called from the context of the embedded frame.
+    fn called_by_own_frame(&self) -> bool {
+        jsc::Context::current().is_some_and(|current| current == self.context)
+    }
+
+    /// Send a request to the greeter on behalf of the main frame if `main_frame`, `None` if no
+    /// reply was received
+    fn send(
+        &self,
+        object: &Object,
+        call: &Call,
+        args: Vec<serde_json::Value>,
+        main_frame: bool,
+    ) -> Option<Result<serde_json::Value, String>> {
+        let page = self.page.upgrade()?;
+        let request = Request::new(object.name, call.name, call.kind, args, main_frame);
+        let message = wwpe::UserMessage::new(object.name, Some(&request.to_json().to_variant()));
+        let reply = MainContext::default()
+            .block_on(page.send_message_to_view_future(&message))
+            .ok()?;
+        match reply
+            .parameters()
+            .and_then(|p| p.str().map(Reply::from_json))?
+        {
+            Ok(Reply::Ok(value)) => Some(Ok(value)),
+            Ok(Reply::Error(e)) => Some(Err(e)),
+            Err(e) => Some(Err(e.to_string())),
+        }
+    }
+
+    /// Instantiate the data classes, such as `LightDMUser`, a value of type `ty` consists of
+    fn wrap(&self, ty: &Type, value: jsc::Value) -> jsc::Value {
+        match ty {
+            Type::Class(class) => self.construct(class, &value).unwrap_or(value),
+            Type::Array(Type::Class(class)) if value.is_array() => {
+                let values: Vec<jsc::Value> = value
+                    .to_vec()
+                    .iter()
+                    .map(|v| self.construct(class, v).unwrap_or_else(|| v.clone()))
+                    .collect();
+                jsc::Value::new_array_from_garray(&self.context, &values)
+            }
+            Type::Nullable(_) if value.is_null() => value,
+            Type::Nullable(ty) => self.wrap(ty, value),
+            _ => value,
+        }
+    }
+
+    fn construct(&self, class: &str, arg: &jsc::Value) -> Option<jsc::Value> {
+        self.classes.get(class)?.constructor_callv(&[arg.clone()])
+    }
+
+    /// `theme_utils.dirlist(path, only_images = true, callback)`, which checks the path before
+    /// asking the greeter and hands the result to `callback` as well
+    fn dirlist(
+        &self,
+        object: &'static Object,
+        call: &'static Call,
+        args: &[jsc::Value],
+    ) -> Option<jsc::Value> {
+        let context = &self.context;
+        let callback = args.get(2).filter(|callback| callback.is_function());
+        let done = |result: jsc::Value| match callback {
+            Some(callback) => callback.function_callv(&[result]),
+            None => Some(result),
+        };
+        let empty = || jsc::Value::new_array_from_garray(context, &[]);
+
+        let path = match args.first() {
+            Some(path) if path.is_string() => path.to_str().to_string(),
+            _ => String::new(),
+        };
+        if path.is_empty() {
+            self.console_error("[ERROR] theme_utils.dirlist(): path must be a non-empty string!");
+            return done(empty());
+        } else if !path.starts_with('/') {
+            self.console_error("[ERROR] theme_utils.dirlist(): path must be absolute!");
+            return done(empty());
+        }
+
+        // No special directory names allowed (eg ../../)
+        let segments: Vec<&str> = path.split('/').collect();
+        let last = segments.len() - 1;
+        let path = segments
+            .iter()
+            .enumerate()
+            .filter(|(index, segment)| {
+                *index == last || segment.is_empty() || !segment.chars().all(|c| c == '.')
+            })
+            .map(|(_, segment)| *segment)
+            .collect::<Vec<_>>()
+            .join("/");
+
+        let only_images = match args.get(1) {
+            Some(only_images) if !only_images.is_undefined() => only_images.clone(),
+            _ => jsc::Value::new_boolean(context, true),
+        };
+        let path = jsc::Value::new_string(context, Some(&path));
+        match self.request(object, call, &[path, only_images]) {
+            Some(result) => {
+                done(result.clone());
+                Some(result)
+            }
+            None => {
+                let error = context
+                    .exception()
+                    .map(|exception| exception.to_str().to_string())
+                    .unwrap_or_default();
+                context.clear_exception();
+                self.console_error(&format!("[ERROR] theme_utils.dirlist(): {error}"));
+                done(empty())
+            }
+        }
+    }
+
+    /// Report an error on the console of the bridge world, which the page can't replace
+    fn console_error(&self, message: &str) {
+        if let Some(console) = self
+            .bridge
+            .global_object()
+            .and_then(|global| global.object_get_property("console"))
+        {
+            let message = jsc::Value::new_string(&self.bridge, Some(message));
+            let _ = console.object_invoke_methodv("error", &[message]);
+        }
+    }
+
+    /// Deliver the event `name` of the theme API object `target`, with `args` the JSON array of
+    /// its arguments: the signal is emitted with the arguments spread, then a `CustomEvent` named
+    /// `<target>:<name>` carrying them as `detail` is dispatched on the window.
+    ///
+    /// Returns whether the event is known to this frame.
+    pub(crate) fn emit(&self, target: &str, name: &str, args: &str) -> bool {
+        let context = &self.context;
+        let Some((object, event)) =
+            protocol::object(target).and_then(|object| Some((object, object.event(name)?)))
+        else {
+            glib::g_warning!("", "{target}.{name} is not a known event");
+            return false;
+        };
+        // Before the callbacks run, as they are likely to read the values it changes
+        self.cache.borrow_mut().invalidate(object, name);
+        if event.main_frame_only && !self.main_frame {
+            return false;
+        }
+
+        let args = jsc::Value::from_json(context, args);
+        if !args.is_array() {
+            glib::g_warning!("", "Arguments of {target}.{name} are not an array");
+            return false;
+        }
+
+        if let Some(signal) = context
+            .global_object()
+            .and_then(|global| global.object_get_property(target))
+            .and_then(|object| object.object_get_property(name))
+            .filter(|signal| signal.is_object())
+        {
+            let _ = signal.object_invoke_methodv("emit", &args.to_vec());
+            // A callback throwing must not keep the event from being dispatched
+            context.clear_exception();
+        }
+        self.dispatch_event(&format!("{target}:{name}"), Some(args));
+
+        true
+    }
+
+    /// Dispatch an event named `name` on the window, a `CustomEvent` if it has a `detail`.
+    ///
+    /// Events are created in the bridge world, unless they carry a `detail`: each world has
+    /// wrappers of its own for DOM objects, so the page would not see the detail set by another
+    /// world. Those are created and dispatched with the functions of the page world looked up
+    /// before the theme could replace them.
+    pub(crate) fn dispatch_event(&self, name: &str, detail: Option<jsc::Value>) {
+        let Some(detail) = detail else {
+            let bridge = &self.bridge;
+            let Some(global) = bridge.global_object() else {
+                return;
+            };
+            let name = jsc::Value::new_string(bridge, Some(name));
+            if let Some(event) = global
+                .object_get_property("Event")
+                .and_then(|event| event.constructor_callv(&[name]))
+            {
+                let _ = global.object_invoke_methodv("dispatchEvent", &[event]);
+            }
+            return;
+        };
+
+        let context = &self.context;
+        let (Some(global), Some(custom_event), Some(dispatch_event), Some(apply)) = (
+            context.global_object(),
+            &self.custom_event,
+            &self.dispatch_event,
+            &self.apply,
+        ) else {
+            return;
+        };
+        let name = jsc::Value::new_string(context, Some(name));
+        let init = jsc::Value::new_object(context, None, None);
+        init.object_set_property("detail", &detail);
+        if let Some(event) = custom_event.constructor_callv(&[name, init]) {
+            let args = jsc::Value::new_array_from_garray(context, &[event]);
+            let _ = apply.function_callv(&[dispatch_event.clone(), global, args]);
+        }
+    }
+}
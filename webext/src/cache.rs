@@ -0,0 +1,201 @@
+// SPDX-FileCopyrightText: 2025 ZaynChen
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use gtk::glib;
+use protocol::{Call, Kind, Object};
+
+use std::collections::HashMap;
+
+/// Values of the getters cached by the theme API of a frame.
+///
+/// Requests block on a nested main loop, during which an event may invalidate the cache, so the
+/// value of a request is only stored if the cache has not been invalidated since it was sent.
+#[derive(Default)]
+pub(crate) struct Cache {
+    version: u64,
+    values: HashMap<(&'static str, &'static str), serde_json::Value>,
+    hits: u64,
+    misses: u64,
+    /// Log the hits, misses and invalidations with the hit and miss counts
+    debug: bool,
+}
+
+impl Cache {
+    pub(crate) fn new(debug: bool) -> Self {
+        Self {
+            debug,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub(crate) fn get(&mut self, object: &Object, getter: &Call) -> Option<serde_json::Value> {
+        let value = self.values.get(&(object.name, getter.name)).cloned();
+        if value.is_some() {
+            self.hits += 1;
+            // Hits are frequent, they are only shown with G_MESSAGES_DEBUG
+            if self.debug {
+                glib::g_debug!(
+                    "",
+                    "{}.{}: cache hit ({} hits, {} misses)",
+                    object.name,
+                    getter.name,
+                    self.hits,
+                    self.misses
+                );
+            }
+        } else {
+            self.misses += 1;
+            if self.debug {
+                glib::g_message!(
+                    "",
+                    "{}.{}: cache miss ({} hits, {} misses)",
+                    object.name,
+                    getter.name,
+                    self.hits,
+                    self.misses
+                );
+            }
+        }
+        value
+    }
+
+    /// Store the value of a request sent at `version`
+    pub(crate) fn insert(
+        &mut self,
+        version: u64,
+        object: &'static Object,
+        getter: &'static Call,
+        value: serde_json::Value,
+    ) {
+        if version == self.version {
+            self.values.insert((object.name, getter.name), value);
+        }
+    }
+
+    /// Drop the values of the getters cached until `event` of `object`
+    pub(crate) fn invalidate(&mut self, object: &Object, event: &str) {
+        let qualified = format!("{}:{event}", object.name);
+        let getters: Vec<(&str, &str)> = protocol::OBJECTS
+            .iter()
+            .flat_map(|owner| owner.calls.iter().map(move |call| (owner, call)))
+            .filter(|(_, call)| call.kind == Kind::Get)
+            .filter(|(owner, call)| {
+                call.cached_until.is_some_and(|events| {
+                    (owner.name == object.name && events.contains(&event))
+                        || events.contains(&qualified.as_str())
+                })
+            })
+            .map(|(owner, call)| (owner.name, call.name))
+            .collect();
+        if getters.is_empty() {
+            return;
+        }
+
+        self.version += 1;
+        self.values.retain(|key, _| !getters.contains(key));
+        if self.debug {
+            let names: Vec<String> = getters
+                .iter()
+                .map(|(owner, getter)| format!("{owner}.{getter}"))
+                .collect();
+            glib::g_message!(
+                "",
+                "{}.{event} invalidated {} ({} hits, {} misses)",
+                object.name,
+                names.join(", "),
+                self.hits,
+                self.misses
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn getter(object: &str, name: &str) -> (&'static Object, &'static Call) {
+        let object = protocol::object(object).unwrap();
+        (object, object.call(name, Kind::Get).unwrap())
+    }
+
+    #[test]
+    fn miss_then_hit() {
+        let mut cache = Cache::default();
+        let (lightdm, users) = getter("lightdm", "users");
+        assert_eq!(cache.get(lightdm, users), None);
+
+        cache.insert(cache.version(), lightdm, users, json!(["alice"]));
+        assert_eq!(cache.get(lightdm, users), Some(json!(["alice"])));
+        assert_eq!(cache.get(lightdm, users), Some(json!(["alice"])));
+        assert_eq!((cache.hits, cache.misses), (2, 1));
+    }
+
+    #[test]
+    fn events_invalidate_their_getters() {
+        let mut cache = Cache::default();
+        let (lightdm, users) = getter("lightdm", "users");
+        let (_, sessions) = getter("lightdm", "sessions");
+        let (_, layouts) = getter("lightdm", "layouts");
+        for getter in [users, sessions, layouts] {
+            cache.insert(cache.version(), lightdm, getter, json!([]));
+        }
+
+        cache.invalidate(lightdm, "users_changed");
+        assert_eq!(cache.get(lightdm, users), None);
+        assert!(cache.get(lightdm, sessions).is_some());
+        assert!(cache.get(lightdm, layouts).is_some());
+
+        cache.invalidate(lightdm, "reset");
+        assert_eq!(cache.get(lightdm, sessions), None);
+        assert_eq!(cache.get(lightdm, layouts), None);
+    }
+
+    #[test]
+    fn events_of_other_objects_invalidate_qualified_getters() {
+        let mut cache = Cache::default();
+        let (lightdm, sessions) = getter("lightdm", "sessions");
+        let (_, layouts) = getter("lightdm", "layouts");
+        let (greeter_config, api) = getter("greeter_config", "api");
+        cache.insert(cache.version(), lightdm, sessions, json!([]));
+        cache.insert(cache.version(), lightdm, layouts, json!([]));
+        cache.insert(cache.version(), greeter_config, api, json!({}));
+
+        cache.invalidate(greeter_config, "config_changed");
+        assert_eq!(cache.get(lightdm, sessions), None);
+        assert_eq!(cache.get(greeter_config, api), None);
+        assert!(cache.get(lightdm, layouts).is_some());
+
+        // The unqualified name only matches the events of the same object
+        cache.insert(cache.version(), greeter_config, api, json!({}));
+        cache.invalidate(lightdm, "config_changed");
+        assert!(cache.get(greeter_config, api).is_some());
+    }
+
+    #[test]
+    fn values_requested_before_an_invalidation_are_dropped() {
+        let mut cache = Cache::default();
+        let (lightdm, users) = getter("lightdm", "users");
+        let version = cache.version();
+
+        // Events caching nothing leave the version alone
+        cache.invalidate(lightdm, "show_prompt");
+        assert_eq!(cache.version(), version);
+
+        // The value was requested before the users changed, it may be stale
+        cache.invalidate(lightdm, "users_changed");
+        assert_eq!(cache.version(), version + 1);
+        cache.insert(version, lightdm, users, json!(["alice"]));
+        assert_eq!(cache.get(lightdm, users), None);
+
+        cache.insert(cache.version(), lightdm, users, json!(["alice", "bob"]));
+        assert_eq!(cache.get(lightdm, users), Some(json!(["alice", "bob"])));
+    }
+}
@@ -2,6 +2,8 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+mod api;
+mod cache;
 mod extension;
 
 use gtk::{
@@ -115,7 +117,11 @@ unsafe extern "C" fn webkit_web_process_extension_initialize_with_user_data(
         web_page_created(page, secure_mode, detect_theme_errors)
     });
 
-    let lightdm_api_script = String::from_variant(&user_data.child_value(2))
+    let prelude_script =
+        String::from_variant(&user_data.child_value(2)).expect("prelude_script is not a String");
+    let lightdm_api_script = String::from_variant(&user_data.child_value(3))
         .expect("lightdm_api_script is not a String");
-    crate::extension::web_page_initialize(lightdm_api_script);
+    let debug_mode =
+        bool::from_variant(&user_data.child_value(4)).expect("debug_mode is not a bool");
+    crate::extension::web_page_initialize(prelude_script, lightdm_api_script, debug_mode);
 }